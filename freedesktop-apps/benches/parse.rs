@@ -0,0 +1,134 @@
+mod baseline;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use freedesktop_apps::{ApplicationEntry, BorrowedDesktopFile};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CORPUS_SIZE: usize = 3000;
+const LOCALES: &[&str] = &[
+    "ar", "ca", "cs", "da", "de", "el", "en_GB", "es", "fi", "fr", "he", "hu", "it", "ja", "ko",
+    "nl", "pl", "pt_BR", "ru", "sv", "tr", "uk", "zh_CN", "zh_TW",
+];
+
+/// A desktop file shaped like a typical distribution entry, with
+/// translations, actions and a few escaped values
+fn synthetic_entry(index: usize) -> String {
+    let mut content = format!(
+        "# Generated entry {index}\n[Desktop Entry]\nType=Application\nVersion=1.5\nName=Application {index}\n"
+    );
+    for locale in LOCALES {
+        content.push_str(&format!("Name[{locale}]=Application {index} ({locale})\n"));
+    }
+    content.push_str(&format!("Comment=Does things\\nwell, number {index}\n"));
+    for locale in LOCALES {
+        content.push_str(&format!("Comment[{locale}]=Comment {index} in {locale}\n"));
+    }
+    content.push_str(&format!(
+        "Icon=app-{index}\nExec=app-{index} --new %U\nTerminal=false\nStartupNotify=true\n\
+         Categories=Utility;Development;\nMimeType=text/plain;text/x-log;\n\
+         Keywords=edit;text\\;plain;code;\nActions=new-window;\n\n\
+         [Desktop Action new-window]\nName=New Window\nExec=app-{index} --new-window\n"
+    ));
+    content
+}
+
+/// The desktop files of a run, written below Cargo's target tmp dir
+/// and removed again when dropped
+struct Corpus {
+    dir: PathBuf,
+    paths: Vec<PathBuf>,
+    contents: Vec<String>,
+}
+
+impl Corpus {
+    fn new() -> Self {
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("parse_bench-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let contents: Vec<String> = (0..CORPUS_SIZE).map(synthetic_entry).collect();
+        let paths = contents
+            .iter()
+            .enumerate()
+            .map(|(index, content)| {
+                let path = dir.join(format!("app-{}.desktop", index));
+                fs::write(&path, content).unwrap();
+                path
+            })
+            .collect();
+
+        Self { dir, paths, contents }
+    }
+}
+
+impl Drop for Corpus {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let corpus = Corpus::new();
+    let (paths, contents) = (&corpus.paths, &corpus.contents);
+
+    // The regex and HashMap parser these replaced
+    let mut group = c.benchmark_group("parse_files");
+    group.sample_size(20);
+    group.bench_function("baseline", |b| {
+        b.iter(|| {
+            for path in paths {
+                let entry = baseline::DesktopEntry::from_path(path).unwrap();
+                black_box(entry.get_desktop_entry_group().and_then(|g| g.get_localized_field("Name", Some("de"))));
+            }
+        })
+    });
+    group.bench_function("ApplicationEntry::from_path", |b| {
+        b.iter(|| {
+            for path in paths {
+                let entry = ApplicationEntry::from_path(path).unwrap();
+                black_box(entry.get_localized_string("Name", Some("de")));
+            }
+        })
+    });
+    group.bench_function("BorrowedDesktopFile::parse", |b| {
+        b.iter(|| {
+            for path in paths {
+                let content = fs::read_to_string(path).unwrap();
+                let file = BorrowedDesktopFile::parse(&content).unwrap();
+                black_box(file.desktop_entry().and_then(|g| g.get_localized("Name", Some("de"))).map(|v| v.as_str().len()));
+            }
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("parse_memory");
+    group.sample_size(20);
+    group.bench_function("baseline", |b| {
+        b.iter(|| {
+            for content in contents {
+                let entry = baseline::DesktopEntry::from_reader("", content.as_bytes()).unwrap();
+                black_box(entry.get_desktop_entry_group().and_then(|g| g.get_localized_field("Name", Some("de"))));
+            }
+        })
+    });
+    group.bench_function("ApplicationEntry::from_str", |b| {
+        b.iter(|| {
+            for content in contents {
+                let entry = ApplicationEntry::from_str(content, None).unwrap();
+                black_box(entry.get_localized_string("Name", Some("de")));
+            }
+        })
+    });
+    group.bench_function("BorrowedDesktopFile::parse", |b| {
+        b.iter(|| {
+            for content in contents {
+                let file = BorrowedDesktopFile::parse(content).unwrap();
+                black_box(file.desktop_entry().and_then(|g| g.get_localized("Name", Some("de"))).map(|v| v.as_str().len()));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
@@ -0,0 +1,122 @@
+use freedesktop_core::info::Info;
+
+use crate::parser::DesktopEntryGroup;
+use crate::{ApplicationEntry, ExecuteError, LaunchContext, LaunchedApp};
+
+/// An additional way to launch an application, from a
+/// `[Desktop Action <id>]` group, e.g. "New Window"
+#[derive(Debug, Clone, Copy)]
+pub struct DesktopAction<'a> {
+    id: &'a str,
+    group: DesktopEntryGroup<'a>,
+    app: &'a ApplicationEntry,
+}
+
+impl ApplicationEntry {
+    /// The actions of this application, in the order of the Actions key.
+    /// Actions without a matching group are skipped.
+    pub fn actions(&self) -> Vec<DesktopAction<'_>> {
+        let ids = self.get_vec("Actions").unwrap_or_default();
+
+        ids.iter()
+            .filter(|id| !id.is_empty())
+            .filter_map(|id| {
+                let group = self.inner.get_group(&format!("Desktop Action {}", id))?;
+                Some(DesktopAction {
+                    id: &group.name()["Desktop Action ".len()..],
+                    group,
+                    app: self,
+                })
+            })
+            .collect()
+    }
+
+    /// Find an action by its identifier
+    pub fn action(&self, id: &str) -> Option<DesktopAction<'_>> {
+        self.actions().into_iter().find(|action| action.id == id)
+    }
+}
+
+impl<'a> DesktopAction<'a> {
+    /// Get the action identifier from the Actions key, e.g. "new-window"
+    pub fn id(&self) -> &'a str {
+        self.id
+    }
+
+    /// Get the application this action belongs to
+    pub fn application(&self) -> &'a ApplicationEntry {
+        self.app
+    }
+
+    /// Get the name in the current locale
+    pub fn name(&self) -> Option<String> {
+        self.get_localized_string("Name", Info::current_locale().as_deref())
+    }
+
+    /// Get the name in a specific locale, e.g. "es_ES"
+    pub fn localized_name(&self, locale: &str) -> Option<String> {
+        self.get_localized_string("Name", Some(locale))
+    }
+
+    /// Get the icon name or path
+    pub fn icon(&self) -> Option<String> {
+        self.get_string("Icon")
+    }
+
+    /// Get the executable command
+    pub fn exec(&self) -> Option<String> {
+        self.get_string("Exec")
+    }
+
+    /// Get a string value from the action group
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.get_localized_string(key, None)
+    }
+
+    /// Get a localized string value from the action group
+    pub fn get_localized_string(&self, key: &str, locale: Option<&str>) -> Option<String> {
+        self.group
+            .get_localized_field(key, locale)
+            .and_then(|value| value.as_string())
+    }
+
+    /// Execute this action with no files
+    pub fn execute(&self) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.execute_with_files(&[])
+    }
+
+    /// Execute this action with the given files, once per file for %f
+    pub fn execute_with_files(&self, files: &[&str]) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.execute_with_context(files, &[], &LaunchContext::default())
+    }
+
+    /// Execute this action with the given URLs, once per URL for %u
+    pub fn execute_with_urls(&self, urls: &[&str]) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.execute_with_context(&[], urls, &LaunchContext::default())
+    }
+
+    /// Execute this action with files or URLs, launched as the context says
+    pub fn execute_with_context(
+        &self,
+        files: &[&str],
+        urls: &[&str],
+        context: &LaunchContext,
+    ) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        let commands = self.prepare_command(files, urls)?;
+        self.app.spawn_all(&commands, context)
+    }
+
+    /// Prepare the commands for execution without actually executing them.
+    /// Field codes and Terminal=true are handled like for the application.
+    pub fn prepare_command(&self, files: &[&str], urls: &[&str]) -> Result<Vec<(String, Vec<String>)>, ExecuteError> {
+        let exec = self
+            .exec()
+            .filter(|exec| !exec.trim().is_empty())
+            .ok_or_else(|| {
+                ExecuteError::NotExecutable(format!("Action '{}' has no Exec key", self.id))
+            })?;
+
+        self.app.validate_try_exec()?;
+        self.app.prepare_exec(&exec, files, urls)
+    }
+}
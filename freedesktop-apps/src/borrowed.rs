@@ -0,0 +1,269 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::parser::{
+    group_header, is_valid_key_name, locale_fallbacks, parse_boolean, unescape_value, warning, ParseOptions,
+};
+use crate::validate::Diagnostic;
+use crate::ParseError;
+
+/// A desktop file parsed in place for fast loading. Groups, keys and
+/// values borrow from the text, and values are only unescaped and
+/// typed when they are read.
+///
+/// ApplicationEntry and the other entry types are loaded with this parser
+/// and keep the text next to the layout it finds. Unlike them, this doesn't
+/// check for required keys, and it can't be edited. Repeated groups are
+/// merged and for repeated keys the last one counts.
+#[derive(Debug, Clone, Default)]
+pub struct BorrowedDesktopFile<'a> {
+    source: &'a str,
+    layout: Layout,
+}
+
+impl<'a> BorrowedDesktopFile<'a> {
+    /// Parse strictly, failing on invalid key names and keys before the first group
+    pub fn parse(content: &'a str) -> Result<Self, ParseError> {
+        Self::parse_with_options(content, &ParseOptions::strict())
+    }
+
+    /// Parse with the given options. In lenient mode invalid lines and a
+    /// byte order mark are skipped without recording warnings.
+    pub fn parse_with_options(content: &'a str, options: &ParseOptions) -> Result<Self, ParseError> {
+        let content = match content.strip_prefix('\u{feff}') {
+            Some(rest) if options.lenient => rest,
+            _ => content,
+        };
+
+        let layout = Layout::scan(content, options, &mut Vec::new())?;
+        Ok(Self {
+            source: content,
+            layout,
+        })
+    }
+
+    /// All groups in file order
+    pub fn groups(&self) -> Vec<BorrowedGroup<'_>> {
+        self.layout.groups(self.source).collect()
+    }
+
+    pub fn group(&self, name: &str) -> Option<BorrowedGroup<'_>> {
+        self.layout.group(self.source, name)
+    }
+
+    /// The [Desktop Entry] group
+    pub fn desktop_entry(&self) -> Option<BorrowedGroup<'_>> {
+        self.group("Desktop Entry")
+    }
+}
+
+/// Where the groups, keys and values of a desktop file are in its text,
+/// so owned entries can keep the text and read values from it lazily
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Layout {
+    groups: Vec<GroupLayout>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GroupLayout {
+    name: Range<usize>,
+    /// Spans of each key and its trimmed raw value, in file order
+    entries: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl Layout {
+    /// Find the groups and keys of desktop file text. Strict parsing fails on
+    /// invalid key names and keys before the first group. Lenient parsing skips
+    /// any line that isn't valid and adds a warning for it.
+    pub(crate) fn scan(content: &str, options: &ParseOptions, warnings: &mut Vec<Diagnostic>) -> Result<Self, ParseError> {
+        let mut groups: Vec<GroupLayout> = Vec::new();
+        let mut current: Option<usize> = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+
+            // Skip empty lines and comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = group_header(line) {
+                current = Some(match groups.iter().position(|g| &content[g.name.clone()] == name) {
+                    Some(index) => index,
+                    None => {
+                        groups.push(GroupLayout {
+                            name: span(content, name),
+                            entries: Vec::new(),
+                        });
+                        groups.len() - 1
+                    }
+                });
+                continue;
+            }
+
+            if options.lenient && line.starts_with('[') {
+                warnings.push(warning(number, 1, "invalid-group-header", &format!("Skipped malformed group header: {}", line)));
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                if options.lenient {
+                    warnings.push(warning(number, 1, "invalid-line", "Skipped line that isn't a comment, group header or key=value pair"));
+                }
+                continue;
+            };
+            let key = key.trim();
+
+            if key.is_empty() || !is_valid_key_name(key) {
+                if options.lenient {
+                    warnings.push(warning(number, 1, "invalid-key-name", &format!("Skipped invalid key name: {}", key)));
+                    continue;
+                } else if key.is_empty() {
+                    continue; // Skip invalid entries
+                }
+                return Err(ParseError::InvalidFormat(format!("Invalid key name: {}", key)));
+            }
+
+            match current {
+                Some(index) => groups[index].entries.push((span(content, key), span(content, value.trim()))),
+                None if options.lenient => {
+                    warnings.push(warning(number, 1, "key-before-group", &format!("Skipped key {} before the first group", key)));
+                }
+                None => {
+                    return Err(ParseError::InvalidFormat(
+                        "Key-value pair found before any group header".to_string(),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self { groups })
+    }
+
+    /// The groups in file order, reading from the text the layout was scanned from
+    pub(crate) fn groups<'a>(&'a self, source: &'a str) -> impl Iterator<Item = BorrowedGroup<'a>> + 'a {
+        self.groups.iter().map(move |layout| BorrowedGroup { source, layout })
+    }
+
+    pub(crate) fn group<'a>(&'a self, source: &'a str, name: &str) -> Option<BorrowedGroup<'a>> {
+        self.groups(source).find(|group| group.name() == name)
+    }
+}
+
+/// The byte range of a slice of content
+fn span(content: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - content.as_ptr() as usize;
+    start..start + part.len()
+}
+
+/// A group of a BorrowedDesktopFile
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedGroup<'a> {
+    source: &'a str,
+    layout: &'a GroupLayout,
+}
+
+impl<'a> BorrowedGroup<'a> {
+    pub fn name(&self) -> &'a str {
+        &self.source[self.layout.name.clone()]
+    }
+
+    /// Keys and values in file order, including localized keys like "Name[de]"
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = (&'a str, RawValue<'a>)> + 'a {
+        let source = self.source;
+        self.layout
+            .entries
+            .iter()
+            .map(move |(key, value)| (&source[key.clone()], RawValue(&source[value.clone()])))
+    }
+
+    /// The value of a key, e.g. "Name" or "Name[de]". For repeated keys the last one counts.
+    pub fn get(&self, key: &str) -> Option<RawValue<'a>> {
+        self.entries()
+            .rev()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The value of a key for a locale, with the spec's locale fallback
+    pub fn get_localized(&self, key: &str, locale: Option<&str>) -> Option<RawValue<'a>> {
+        if let Some(locale) = locale {
+            for candidate in locale_fallbacks(locale) {
+                let found = self
+                    .entries()
+                    .rev()
+                    .find(|(k, _)| key_locale(k, key) == Some(candidate.as_str()));
+                if let Some((_, value)) = found {
+                    return Some(value);
+                }
+            }
+        }
+
+        self.get(key)
+    }
+}
+
+/// The locale of a localized key like "Name[de]" when its base is `key`
+fn key_locale<'a>(localized: &'a str, key: &str) -> Option<&'a str> {
+    localized
+        .strip_prefix(key)?
+        .strip_prefix('[')?
+        .strip_suffix(']')
+}
+
+/// A value as written in the file, with escape sequences intact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawValue<'a>(&'a str);
+
+impl<'a> RawValue<'a> {
+    pub fn raw(&self) -> &'a str {
+        self.0
+    }
+
+    /// The unescaped string, borrowed when there is nothing to unescape
+    pub fn as_str(&self) -> Cow<'a, str> {
+        if self.0.contains('\\') {
+            Cow::Owned(unescape_value(self.0))
+        } else {
+            Cow::Borrowed(self.0)
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        parse_boolean(self.0)
+    }
+
+    pub fn as_numeric(&self) -> Option<f64> {
+        self.as_str().parse().ok()
+    }
+
+    /// The unescaped items of a list value
+    pub fn as_list(&self) -> Vec<Cow<'a, str>> {
+        let mut items = Vec::new();
+        let mut start = 0;
+        let mut escaped = false;
+
+        for (index, ch) in self.0.char_indices() {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                ';' => {
+                    push_item(&mut items, &self.0[start..index]);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        push_item(&mut items, &self.0[start..]);
+
+        items
+    }
+}
+
+fn push_item<'a>(items: &mut Vec<Cow<'a, str>>, item: &'a str) {
+    let item = item.trim();
+    if !item.is_empty() {
+        items.push(RawValue(item).as_str());
+    }
+}
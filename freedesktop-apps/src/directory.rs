@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use freedesktop_core::info::Info;
+
+use crate::parser::{DesktopEntry, ParseOptions};
+use crate::{data_dirs, entry_files_in, EntryGroup, FindError, ParseError};
+
+/// The desktop-directories dirs of $XDG_DATA_HOME and $XDG_DATA_DIRS,
+/// most important first
+pub fn directory_entry_paths() -> Vec<PathBuf> {
+    data_dirs()
+        .iter()
+        .map(|path| path.join("desktop-directories"))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// A `Type=Directory` desktop entry, describing a menu
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryEntry {
+    pub(crate) inner: DesktopEntry,
+}
+
+impl DirectoryEntry {
+    /// Load a .directory file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::from_path_with_options(path, &ParseOptions::strict())
+    }
+
+    pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, ParseError> {
+        let desktop_entry = DesktopEntry::from_path_with_options(path, options)?;
+        Ok(DirectoryEntry {
+            inner: desktop_entry,
+        })
+    }
+
+    /// Find a directory entry by its ID, e.g. "Games.directory" or
+    /// "kde-utilities.directory" for kde/utilities.directory. The first
+    /// desktop-directories dir containing the ID wins, and a Hidden=true
+    /// entry there counts as deleted. Files are parsed leniently, like
+    /// applications found by ID.
+    pub fn from_id(id: &str) -> Result<Self, FindError> {
+        let id = if id.ends_with(".directory") {
+            id.to_string()
+        } else {
+            format!("{}.directory", id)
+        };
+
+        for dir in directory_entry_paths() {
+            if let Some(path) = find_in(&dir, &id) {
+                let entry = Self::from_path_with_options(&path, &ParseOptions::lenient())
+                    .map_err(|e| FindError::InvalidEntry(path.clone(), e))?;
+                if entry.is_hidden() {
+                    break;
+                }
+                return Ok(entry);
+            }
+        }
+
+        Err(FindError::NotFound(format!("No directory entry found for ID: {}", id)))
+    }
+
+    /// Get the name in the current locale
+    pub fn name(&self) -> Option<String> {
+        self.get_localized_string("Name", Info::current_locale().as_deref())
+    }
+
+    /// Get the comment in the current locale
+    pub fn comment(&self) -> Option<String> {
+        self.get_localized_string("Comment", Info::current_locale().as_deref())
+    }
+
+    /// Get the icon name or path in the current locale
+    pub fn icon(&self) -> Option<String> {
+        self.get_localized_string("Icon", Info::current_locale().as_deref())
+    }
+
+    /// Get the name in a specific locale, e.g. "de_DE"
+    pub fn localized_name(&self, locale: &str) -> Option<String> {
+        self.get_localized_string("Name", Some(locale))
+    }
+
+    /// Get the comment in a specific locale
+    pub fn localized_comment(&self, locale: &str) -> Option<String> {
+        self.get_localized_string("Comment", Some(locale))
+    }
+
+    /// Get a string value from the Desktop Entry group
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.get_localized_string(key, None)
+    }
+
+    /// Get a localized string value from the Desktop Entry group
+    pub fn get_localized_string(&self, key: &str, locale: Option<&str>) -> Option<String> {
+        self.inner
+            .get_desktop_entry_group()
+            .and_then(|group| group.get_localized_field(key, locale))
+            .and_then(|value| value.as_string())
+    }
+
+    /// Get a boolean value from the Desktop Entry group
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.inner
+            .get_desktop_entry_group()
+            .and_then(|group| group.get_field(key))
+            .and_then(|value| value.as_bool())
+    }
+
+    /// Any group of the file by name, e.g. "X-Flatpak"
+    pub fn group(&self, name: &str) -> Option<EntryGroup<'_>> {
+        self.inner.group(name)
+    }
+
+    /// Names of all groups, in file order
+    pub fn groups(&self) -> Vec<&str> {
+        self.inner.group_names()
+    }
+
+    /// Get the file path of this directory entry
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Get the entry type, normally "Directory"
+    pub fn entry_type(&self) -> Option<String> {
+        self.get_string("Type")
+    }
+
+    /// Check if the entry is deleted
+    pub fn is_hidden(&self) -> bool {
+        self.get_bool("Hidden").unwrap_or(false)
+    }
+
+    /// Check if the menu it describes should not be displayed
+    pub fn no_display(&self) -> bool {
+        self.get_bool("NoDisplay").unwrap_or(false)
+    }
+}
+
+/// Path of the .directory file with the given ID inside `dir`
+pub(crate) fn find_in(dir: &Path, id: &str) -> Option<PathBuf> {
+    let direct = dir.join(id);
+    if direct.is_file() {
+        return Some(direct);
+    }
+
+    // IDs of files in subdirectories have '-' in place of '/'
+    if !id.contains('-') {
+        return None;
+    }
+
+    entry_files_in(dir, "directory")
+        .into_iter()
+        .find(|(file_id, _)| file_id == id)
+        .map(|(_, path)| path)
+}
@@ -0,0 +1,269 @@
+use std::path::Path;
+
+use crate::ParseError;
+
+/// A line of a desktop file as it was written
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    Blank(String),
+    Comment(String),
+    Group { raw: String, name: String },
+    Entry { raw: String, key: String, value: String },
+    /// Anything else, kept so it can be written back
+    Other(String),
+}
+
+impl Line {
+    fn parse(raw: &str) -> Line {
+        let line = raw.trim();
+
+        if line.is_empty() {
+            Line::Blank(raw.to_string())
+        } else if line.starts_with('#') {
+            Line::Comment(raw.to_string())
+        } else if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            Line::Group {
+                raw: raw.to_string(),
+                name: name.to_string(),
+            }
+        } else if let Some((key, value)) = line.split_once('=') {
+            Line::Entry {
+                raw: raw.to_string(),
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            }
+        } else {
+            Line::Other(raw.to_string())
+        }
+    }
+
+    fn raw(&self) -> &str {
+        match self {
+            Line::Blank(raw) | Line::Comment(raw) | Line::Other(raw) => raw,
+            Line::Group { raw, .. } | Line::Entry { raw, .. } => raw,
+        }
+    }
+}
+
+/// A desktop file kept exactly as written, including comments, blank lines,
+/// key order and escaping, so it can be written back byte for byte.
+///
+/// Values are the raw text after the '=', with escape sequences intact.
+/// Use escape_value() and escape_list() to produce them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesktopFile {
+    lines: Vec<Line>,
+    final_newline: bool,
+    crlf: bool,
+}
+
+impl DesktopFile {
+    /// Parse desktop file text. This never fails, lines that
+    /// aren't valid are kept as they are.
+    pub fn parse(content: &str) -> Self {
+        let final_newline = content.ends_with('\n');
+        let body = content.strip_suffix('\n').unwrap_or(content);
+
+        let lines: Vec<Line> = if content.is_empty() {
+            Vec::new()
+        } else {
+            body.split('\n').map(Line::parse).collect()
+        };
+        let crlf = lines.first().is_some_and(|l| l.raw().ends_with('\r'));
+
+        Self {
+            lines,
+            final_newline,
+            crlf,
+        }
+    }
+
+    /// Read a desktop file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let content = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| ParseError::IoError(format!("Failed to read file: {}", e)))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Write the file, creating its parent directories
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_string())
+    }
+
+    /// Names of the groups, in file order
+    pub fn groups(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Group { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Keys of a group in file order, including localized ones like "Name[de]"
+    pub fn keys(&self, group: &str) -> Vec<&str> {
+        self.group_lines(group)
+            .filter_map(|index| match &self.lines[index] {
+                Line::Entry { key, .. } => Some(key.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The raw value of a key. For repeated keys the last one counts.
+    pub fn get(&self, group: &str, key: &str) -> Option<&str> {
+        self.find_key(group, key).map(|index| match &self.lines[index] {
+            Line::Entry { value, .. } => value.as_str(),
+            _ => unreachable!(),
+        })
+    }
+
+    /// Set the raw value of a key. Existing keys are changed in place, new
+    /// ones go after the other translations of the key, or at the end of the
+    /// group. The group is added at the end of the file if it's missing.
+    pub fn set(&mut self, group: &str, key: &str, value: &str) {
+        let raw = format!("{}={}{}", key, value, self.line_end());
+        let line = Line::Entry {
+            raw,
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        if let Some(index) = self.find_key(group, key) {
+            self.lines[index] = line;
+            return;
+        }
+
+        if !self.groups().contains(&group) {
+            self.add_group(group);
+        }
+
+        let base_key = key.split('[').next().unwrap_or(key);
+        let entries: Vec<usize> = self
+            .group_lines(group)
+            .filter(|&index| matches!(self.lines[index], Line::Entry { .. }))
+            .collect();
+
+        let position = entries
+            .iter()
+            .rev()
+            .find(|&&index| match &self.lines[index] {
+                Line::Entry { key, .. } => key.split('[').next() == Some(base_key),
+                _ => false,
+            })
+            .or(entries.last())
+            .map(|index| index + 1)
+            .unwrap_or_else(|| self.group_header(group).map(|i| i + 1).unwrap_or(self.lines.len()));
+
+        self.lines.insert(position, line);
+    }
+
+    /// Remove a key, returning whether it was present
+    pub fn remove(&mut self, group: &str, key: &str) -> bool {
+        let indices: Vec<usize> = self
+            .group_lines(group)
+            .filter(|&index| matches!(&self.lines[index], Line::Entry { key: k, .. } if k == key))
+            .collect();
+
+        for index in indices.iter().rev() {
+            self.lines.remove(*index);
+        }
+
+        !indices.is_empty()
+    }
+
+    fn line_end(&self) -> &'static str {
+        if self.crlf {
+            "\r"
+        } else {
+            ""
+        }
+    }
+
+    fn add_group(&mut self, group: &str) {
+        if !self.lines.is_empty() && !matches!(self.lines.last(), Some(Line::Blank(_))) {
+            self.lines.push(Line::Blank(self.line_end().to_string()));
+        }
+        self.final_newline = true;
+
+        self.lines.push(Line::Group {
+            raw: format!("[{}]{}", group, self.line_end()),
+            name: group.to_string(),
+        });
+    }
+
+    fn group_header(&self, group: &str) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| matches!(line, Line::Group { name, .. } if name == group))
+    }
+
+    /// Indices of the lines in a group, after its header. Duplicate
+    /// groups are treated as one, like the parser does.
+    fn group_lines<'a>(&'a self, group: &'a str) -> impl Iterator<Item = usize> + 'a {
+        let mut current: Option<&str> = None;
+
+        self.lines.iter().enumerate().filter_map(move |(index, line)| {
+            if let Line::Group { name, .. } = line {
+                current = Some(name.as_str());
+                return None;
+            }
+            (current == Some(group)).then_some(index)
+        })
+    }
+
+    fn find_key(&self, group: &str, key: &str) -> Option<usize> {
+        self.group_lines(group)
+            .filter(|&index| matches!(&self.lines[index], Line::Entry { key: k, .. } if k == key))
+            .last()
+    }
+}
+
+impl std::fmt::Display for DesktopFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, line) in self.lines.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(line.raw())?;
+        }
+
+        if self.final_newline {
+            f.write_str("\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape a string value: backslash, newline, tab and carriage
+/// return, plus leading and trailing spaces so they aren't trimmed away
+pub fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let last = value.chars().count().saturating_sub(1);
+
+    for (index, ch) in value.chars().enumerate() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            ' ' if index == 0 || index == last => escaped.push_str("\\s"),
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// Escape a list value, with a trailing ';' as the spec recommends
+pub fn escape_list<S: AsRef<str>>(items: &[S]) -> String {
+    items
+        .iter()
+        .map(|item| format!("{};", escape_value(item.as_ref()).replace(';', "\\;")))
+        .collect()
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+use crate::document::{escape_list, escape_value};
+use crate::parser::is_valid_key_name;
+use crate::{ApplicationEntry, ParseError};
+
+const GROUP: &str = "Desktop Entry";
+
+#[derive(Debug, Clone)]
+pub enum EditError {
+    InvalidKey(String),
+    NoId(String),
+    IoError(String),
+    /// The edited document no longer parses
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::InvalidKey(msg) => write!(f, "Invalid key: {}", msg),
+            EditError::NoId(msg) => write!(f, "Desktop entry has no ID: {}", msg),
+            EditError::IoError(msg) => write!(f, "I/O error: {}", msg),
+            EditError::Parse(err) => write!(f, "Parse error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Path of the user override for a desktop file ID:
+/// $XDG_DATA_HOME/applications/<id>.desktop
+pub fn user_override_path(id: &str) -> PathBuf {
+    let file_name = if id.ends_with(".desktop") {
+        id.to_string()
+    } else {
+        format!("{}.desktop", id)
+    };
+
+    freedesktop_core::xdg_data_home()
+        .join("applications")
+        .join(file_name)
+}
+
+/// Editing. Changes are made to the in-memory entry, keeping the comments
+/// and layout of the file, and written by save() as a user override that
+/// takes precedence over the system file with the same ID.
+impl ApplicationEntry {
+    /// Set a string value in the Desktop Entry group
+    pub fn set_string(&mut self, key: &str, value: &str) -> Result<(), EditError> {
+        self.set_raw(key, &escape_value(value))
+    }
+
+    /// Set the translation of a value for a locale, e.g. ("Name", "de", "Rechner")
+    pub fn set_localized(&mut self, key: &str, locale: &str, value: &str) -> Result<(), EditError> {
+        let valid_locale = !locale.is_empty()
+            && locale
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '@' | '.' | '-'));
+        if !valid_locale {
+            return Err(EditError::InvalidKey(format!("{}[{}]", key, locale)));
+        }
+
+        self.set_raw(&format!("{}[{}]", key, locale), &escape_value(value))
+    }
+
+    /// Set a list value, e.g. Categories or Keywords
+    pub fn set_list<S: AsRef<str>>(&mut self, key: &str, values: &[S]) -> Result<(), EditError> {
+        self.set_raw(key, &escape_list(values))
+    }
+
+    /// Remove a key, e.g. "Comment" or "Name[de]"
+    pub fn remove_key(&mut self, key: &str) -> Result<(), EditError> {
+        if self.inner.document_mut().remove(GROUP, key) {
+            self.reload()?;
+        }
+        Ok(())
+    }
+
+    /// Set Hidden, which makes the entry count as deleted once saved
+    pub fn set_hidden(&mut self, hidden: bool) -> Result<(), EditError> {
+        self.set_raw("Hidden", if hidden { "true" } else { "false" })
+    }
+
+    /// Path the user override for this entry is saved to
+    pub fn override_path(&self) -> Option<PathBuf> {
+        self.id().map(|id| user_override_path(&id))
+    }
+
+    /// Save the entry to $XDG_DATA_HOME/applications/<id>.desktop, where
+    /// it overrides the system file. The entry then refers to that file.
+    pub fn save(&mut self) -> Result<PathBuf, EditError> {
+        let path = self
+            .override_path()
+            .ok_or_else(|| EditError::NoId(self.path().display().to_string()))?;
+
+        self.inner
+            .document()
+            .save(&path)
+            .map_err(|e| EditError::IoError(format!("Failed to write {}: {}", path.display(), e)))?;
+
+        let id = self.id().unwrap_or_default();
+        self.inner.path = path.clone();
+        self.id = Some(format!("{}.desktop", id));
+
+        Ok(path)
+    }
+
+    /// Go back to the system version of this entry by deleting the user override.
+    /// Load the entry again with from_id() to see the restored version.
+    pub fn restore_default(&self) -> Result<bool, EditError> {
+        let id = self
+            .id()
+            .ok_or_else(|| EditError::NoId(self.path().display().to_string()))?;
+
+        Self::remove_override(&id)
+    }
+
+    /// Delete the user override for a desktop file ID, returning whether there
+    /// was one. Works for entries hidden with set_hidden(), which from_id()
+    /// no longer finds.
+    pub fn remove_override(id: &str) -> Result<bool, EditError> {
+        let path = user_override_path(id);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        std::fs::remove_file(&path)
+            .map_err(|e| EditError::IoError(format!("Failed to remove {}: {}", path.display(), e)))?;
+        Ok(true)
+    }
+
+    fn set_raw(&mut self, key: &str, value: &str) -> Result<(), EditError> {
+        if key.is_empty() || !is_valid_key_name(key) {
+            return Err(EditError::InvalidKey(key.to_string()));
+        }
+
+        self.inner.document_mut().set(GROUP, key, value);
+        self.reload()
+    }
+
+    fn reload(&mut self) -> Result<(), EditError> {
+        self.inner
+            .reload_document()
+            .map_err(EditError::Parse)
+    }
+}
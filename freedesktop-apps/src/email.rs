@@ -0,0 +1,278 @@
+use std::path::{Path, PathBuf};
+
+use crate::{ApplicationEntry, ExecuteError, LaunchContext, LaunchedApp};
+
+/// Mail clients that need special handling when composing,
+/// mirroring the client detection done by xdg-email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailClient {
+    /// Thunderbird and its forks ignore attachments in mailto URIs,
+    /// so they are launched with `-compose` instead.
+    Thunderbird,
+    /// Evolution reads `attach=` parameters from the mailto URI.
+    Evolution,
+    /// Any other client. The mailto URI is passed as is and
+    /// attachments are added as `attach=` parameters, which
+    /// many clients silently ignore.
+    Other,
+}
+
+impl MailClient {
+    /// Detect the client from the program in the handler's Exec key,
+    /// e.g. /usr/bin/thunderbird or thunderbird-bin
+    pub fn detect(handler: &ApplicationEntry) -> Self {
+        let program = handler
+            .exec_command()
+            .ok()
+            .and_then(|command| command.argv(handler, &[], &[]).into_iter().next())
+            .unwrap_or_default();
+        let name = Path::new(&program)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if ["thunderbird", "icedove", "seamonkey", "betterbird"]
+            .iter()
+            .any(|client| name.starts_with(client))
+        {
+            MailClient::Thunderbird
+        } else if name.starts_with("evolution") {
+            MailClient::Evolution
+        } else {
+            MailClient::Other
+        }
+    }
+}
+
+/// An email to open in the user's mail client, equivalent to xdg-email.
+///
+/// ```no_run
+/// use freedesktop_apps::Email;
+///
+/// Email::new()
+///     .to("bugs@example.com")
+///     .subject("Crash report")
+///     .body("Steps to reproduce:\n")
+///     .attach("/tmp/crash.log")
+///     .compose()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Email {
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    subject: Option<String>,
+    body: Option<String>,
+    attachments: Vec<PathBuf>,
+}
+
+impl Email {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a recipient
+    pub fn to<S: Into<String>>(mut self, address: S) -> Self {
+        self.to.push(address.into());
+        self
+    }
+
+    /// Add a carbon copy recipient
+    pub fn cc<S: Into<String>>(mut self, address: S) -> Self {
+        self.cc.push(address.into());
+        self
+    }
+
+    /// Add a blind carbon copy recipient
+    pub fn bcc<S: Into<String>>(mut self, address: S) -> Self {
+        self.bcc.push(address.into());
+        self
+    }
+
+    pub fn subject<S: Into<String>>(mut self, subject: S) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    pub fn body<S: Into<String>>(mut self, body: S) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Attach a file. The file must exist when the email is composed.
+    pub fn attach<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.attachments.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Build the mailto URI as defined by RFC 6068, without attachments
+    pub fn mailto_uri(&self) -> String {
+        self.build_mailto(&[])
+    }
+
+    /// Open the composer of the default mail client, returning the launched process
+    pub fn compose(&self) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.compose_with_context(&LaunchContext::default())
+    }
+
+    /// Open the composer of the default mail client, launched as the context says
+    pub fn compose_with_context(&self, context: &LaunchContext) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        let handler = ApplicationEntry::default_mail_client()
+            .ok_or_else(|| ExecuteError::NoHandler("x-scheme-handler/mailto".to_string()))?;
+
+        self.launch(&handler, context)
+    }
+
+    /// Open the composer of a specific mail client
+    pub fn compose_with(&self, handler: &ApplicationEntry) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.launch(handler, &LaunchContext::default())
+    }
+
+    fn launch(&self, handler: &ApplicationEntry, context: &LaunchContext) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        let command = self.prepare_command(handler)?;
+        handler.spawn_all(&[command], context)
+    }
+
+    /// Prepare the command for composing without actually executing it (for testing)
+    pub fn prepare_command(&self, handler: &ApplicationEntry) -> Result<(String, Vec<String>), ExecuteError> {
+        let attachments = self.attachment_uris()?;
+
+        match MailClient::detect(handler) {
+            MailClient::Thunderbird => {
+                // Without files there is always a single command
+                let (program, mut args) = handler.prepare_command(&[], &[])?.remove(0);
+                args.push("-compose".to_string());
+                args.push(self.thunderbird_compose_string(&attachments));
+                Ok((program, args))
+            }
+            MailClient::Evolution | MailClient::Other => {
+                let uri = self.build_mailto(&attachments);
+                Ok(handler.prepare_command(&[], &[&uri])?.remove(0))
+            }
+        }
+    }
+
+    fn attachment_uris(&self) -> Result<Vec<String>, ExecuteError> {
+        self.attachments
+            .iter()
+            .map(|path| {
+                let absolute = path.canonicalize().map_err(|_| {
+                    ExecuteError::ValidationFailed(format!(
+                        "Attachment '{}' does not exist",
+                        path.display()
+                    ))
+                })?;
+
+                Ok(format!(
+                    "file://{}",
+                    percent_encode(&absolute.to_string_lossy(), b"/")
+                ))
+            })
+            .collect()
+    }
+
+    fn build_mailto(&self, attachments: &[String]) -> String {
+        let encode_addresses = |addresses: &[String]| {
+            addresses
+                .iter()
+                .map(|a| percent_encode(a, b"@"))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut params: Vec<String> = Vec::new();
+
+        if !self.cc.is_empty() {
+            params.push(format!("cc={}", encode_addresses(&self.cc)));
+        }
+        if !self.bcc.is_empty() {
+            params.push(format!("bcc={}", encode_addresses(&self.bcc)));
+        }
+        if let Some(subject) = &self.subject {
+            params.push(format!("subject={}", percent_encode(subject, b"")));
+        }
+        if let Some(body) = &self.body {
+            // RFC 6068 requires line breaks in the body to be CRLF
+            let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+            params.push(format!("body={}", percent_encode(&body, b"")));
+        }
+        for attachment in attachments {
+            params.push(format!("attach={}", percent_encode(attachment, b"")));
+        }
+
+        let mut uri = format!("mailto:{}", encode_addresses(&self.to));
+        if !params.is_empty() {
+            uri.push('?');
+            uri.push_str(&params.join("&"));
+        }
+
+        uri
+    }
+
+    /// Thunderbird's -compose argument: comma separated key='value' pairs.
+    /// Thunderbird unescapes %XX in the values, so quotes and commas in
+    /// them are encoded to keep them from ending the value or the field.
+    fn thunderbird_compose_string(&self, attachments: &[String]) -> String {
+        let encode_addresses = |addresses: &[String]| {
+            addresses
+                .iter()
+                .map(|a| compose_encode(a))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut fields: Vec<String> = Vec::new();
+
+        if !self.to.is_empty() {
+            fields.push(format!("to='{}'", encode_addresses(&self.to)));
+        }
+        if !self.cc.is_empty() {
+            fields.push(format!("cc='{}'", encode_addresses(&self.cc)));
+        }
+        if !self.bcc.is_empty() {
+            fields.push(format!("bcc='{}'", encode_addresses(&self.bcc)));
+        }
+        if let Some(subject) = &self.subject {
+            fields.push(format!("subject='{}'", compose_encode(subject)));
+        }
+        if let Some(body) = &self.body {
+            fields.push(format!("body='{}'", compose_encode(body)));
+        }
+        if !attachments.is_empty() {
+            fields.push(format!("attachment='{}'", attachments.join(",")));
+        }
+
+        fields.join(",")
+    }
+}
+
+/// Percent-encode the characters with a meaning in a -compose value
+fn compose_encode(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+
+    for ch in input.chars() {
+        match ch {
+            '%' | '\'' | ',' => result.push_str(&format!("%{:02X}", ch as u8)),
+            ch => result.push(ch),
+        }
+    }
+
+    result
+}
+
+/// Percent-encode everything except unreserved characters
+/// and the extra bytes in `keep`
+fn percent_encode(input: &str, keep: &[u8]) -> String {
+    let mut result = String::with_capacity(input.len());
+
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.contains(&byte) {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    result
+}
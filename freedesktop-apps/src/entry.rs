@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use freedesktop_core::info::Info;
+
+use crate::parser::{DesktopEntry, ParseOptions};
+use crate::{ApplicationEntry, DesktopFileIndex, DirectoryEntry, FindError, LinkEntry, ParseError};
+
+/// Any kind of desktop entry, chosen by its Type key
+#[derive(Debug, Clone)]
+pub enum Entry {
+    Application(ApplicationEntry),
+    Link(LinkEntry),
+    Directory(DirectoryEntry),
+}
+
+impl Entry {
+    /// Load a .desktop or .directory file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::from_desktop_entry(DesktopEntry::from_path(path)?)
+    }
+
+    pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, ParseError> {
+        Self::from_desktop_entry(DesktopEntry::from_path_with_options(path, options)?)
+    }
+
+    /// Find an application or link by its desktop file ID
+    pub fn from_id(id: &str) -> Result<Self, FindError> {
+        DesktopFileIndex::load().find_entry(id)
+    }
+
+    /// Get all application and link entries from standard directories,
+    /// each desktop file ID only once
+    pub fn all() -> Vec<Entry> {
+        DesktopFileIndex::load().entries()
+    }
+
+    pub(crate) fn from_desktop_entry(inner: DesktopEntry) -> Result<Self, ParseError> {
+        let entry_type = inner
+            .get_desktop_entry_group()
+            .and_then(|group| group.get_field("Type"))
+            .and_then(|value| value.as_string())
+            .unwrap_or_default();
+
+        match entry_type.as_str() {
+            "Application" => Ok(Entry::Application(ApplicationEntry { inner, id: None })),
+            "Link" => Ok(Entry::Link(LinkEntry { inner })),
+            "Directory" => Ok(Entry::Directory(DirectoryEntry { inner })),
+            other => Err(ParseError::InvalidFormat(format!("Unknown entry type: {}", other))),
+        }
+    }
+
+    /// Get the entry name in the current locale
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Entry::Application(entry) => entry.get_localized_string("Name", Info::current_locale().as_deref()),
+            Entry::Link(entry) => entry.name(),
+            Entry::Directory(entry) => entry.name(),
+        }
+    }
+
+    /// Get the icon name or path
+    pub fn icon(&self) -> Option<String> {
+        match self {
+            Entry::Application(entry) => entry.icon(),
+            Entry::Link(entry) => entry.icon(),
+            Entry::Directory(entry) => entry.icon(),
+        }
+    }
+
+    /// Check if the entry is deleted (Hidden=true)
+    pub fn is_hidden(&self) -> bool {
+        match self {
+            Entry::Application(entry) => entry.is_hidden(),
+            Entry::Link(entry) => entry.is_hidden(),
+            Entry::Directory(entry) => entry.is_hidden(),
+        }
+    }
+
+    /// Get the file path of the entry
+    pub fn path(&self) -> &Path {
+        match self {
+            Entry::Application(entry) => entry.path(),
+            Entry::Link(entry) => entry.path(),
+            Entry::Directory(entry) => entry.path(),
+        }
+    }
+
+    pub fn as_application(&self) -> Option<&ApplicationEntry> {
+        match self {
+            Entry::Application(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&LinkEntry> {
+        match self {
+            Entry::Link(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn as_directory(&self) -> Option<&DirectoryEntry> {
+        match self {
+            Entry::Directory(entry) => Some(entry),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use freedesktop_core::info::Info;
+
+use crate::{ApplicationEntry, ExecuteError};
+
+/// Characters the spec only allows inside a quoted argument. The single
+/// quote is also reserved, but accepted for quoting like GLib does.
+const RESERVED: &[char] = &['`', '$', '|', '&', ';', '<', '>', '(', ')', '*', '?', '#', '~'];
+
+/// Characters that must be escaped with a backslash inside double quotes
+const QUOTED_ESCAPES: &[char] = &['"', '`', '$', '\\'];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecError {
+    Empty,
+    UnterminatedQuote,
+    /// A reserved character outside of quotes, such as `$` or `|`
+    ReservedCharacter(char),
+    /// A backslash inside double quotes followed by a character that can't be escaped
+    InvalidEscape(char),
+    /// A field code inside a quoted argument, which the spec forbids
+    FieldCodeInQuotes(String),
+    InvalidFieldCode(String),
+    /// %F, %U or %i as part of a larger argument, they expand to several arguments
+    NotStandalone(String),
+}
+
+impl std::fmt::Display for ExecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecError::Empty => write!(f, "Empty command"),
+            ExecError::UnterminatedQuote => write!(f, "Unterminated quote"),
+            ExecError::ReservedCharacter(c) => write!(f, "Reserved character '{}' must be quoted", c),
+            ExecError::InvalidEscape(c) => write!(f, "Invalid escape sequence \\{} in quoted argument", c),
+            ExecError::FieldCodeInQuotes(code) => write!(f, "Field code {} can't be used inside quotes", code),
+            ExecError::InvalidFieldCode(code) => write!(f, "Invalid field code {}", code),
+            ExecError::NotStandalone(code) => write!(f, "Field code {} must be an argument by itself", code),
+        }
+    }
+}
+
+impl std::error::Error for ExecError {}
+
+impl From<ExecError> for ExecuteError {
+    fn from(err: ExecError) -> Self {
+        ExecuteError::InvalidCommand(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    FieldCode(char),
+}
+
+/// An Exec value split into arguments following the Desktop Entry
+/// Specification, with field codes kept for expansion.
+///
+/// Field codes are substituted argument by argument, so file names and
+/// URLs are never parsed again and need no escaping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecCommand {
+    args: Vec<Vec<Piece>>,
+}
+
+impl ExecCommand {
+    /// Tokenize an Exec value. String escapes must already be undone,
+    /// as ApplicationEntry::exec() does.
+    pub fn parse(exec: &str) -> Result<Self, ExecError> {
+        let mut args: Vec<Vec<Piece>> = Vec::new();
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut text = String::new();
+        let mut in_arg = false;
+        let mut chars = exec.chars();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                ' ' | '\t' | '\n' => {
+                    if in_arg {
+                        finish_text(&mut pieces, &mut text);
+                        args.push(std::mem::take(&mut pieces));
+                        in_arg = false;
+                    }
+                    continue;
+                }
+                '"' => loop {
+                    match chars.next() {
+                        None => return Err(ExecError::UnterminatedQuote),
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) if QUOTED_ESCAPES.contains(&c) => text.push(c),
+                            Some(c) => return Err(ExecError::InvalidEscape(c)),
+                            None => return Err(ExecError::UnterminatedQuote),
+                        },
+                        Some('%') => quoted_percent(&mut chars, &mut text)?,
+                        Some(c) => text.push(c),
+                    }
+                },
+                '\'' => loop {
+                    match chars.next() {
+                        None => return Err(ExecError::UnterminatedQuote),
+                        Some('\'') => break,
+                        Some('%') => quoted_percent(&mut chars, &mut text)?,
+                        Some(c) => text.push(c),
+                    }
+                },
+                '\\' => text.push(chars.next().unwrap_or('\\')),
+                '%' => match chars.next() {
+                    Some('%') => text.push('%'),
+                    Some(code @ ('f' | 'F' | 'u' | 'U' | 'i' | 'c' | 'k')) => {
+                        if !text.is_empty() {
+                            pieces.push(Piece::Text(std::mem::take(&mut text)));
+                        }
+                        pieces.push(Piece::FieldCode(code));
+                    }
+                    // Deprecated field codes are removed, along with their argument if empty
+                    Some('d' | 'D' | 'n' | 'N' | 'v' | 'm') => continue,
+                    Some(code) => return Err(ExecError::InvalidFieldCode(format!("%{}", code))),
+                    None => return Err(ExecError::InvalidFieldCode("%".to_string())),
+                },
+                c if RESERVED.contains(&c) => return Err(ExecError::ReservedCharacter(c)),
+                c => text.push(c),
+            }
+            in_arg = true;
+        }
+
+        if in_arg {
+            finish_text(&mut pieces, &mut text);
+            args.push(pieces);
+        }
+
+        for arg in &args {
+            for piece in arg {
+                if let Piece::FieldCode(code @ ('F' | 'U' | 'i')) = piece {
+                    if arg.len() > 1 {
+                        return Err(ExecError::NotStandalone(format!("%{}", code)));
+                    }
+                }
+            }
+        }
+
+        match args.first().map(|arg| arg.as_slice()) {
+            None | Some([Piece::FieldCode(_)]) => Err(ExecError::Empty),
+            _ => Ok(Self { args }),
+        }
+    }
+
+    /// The field codes used, in order, e.g. ['F', 'i']
+    pub fn field_codes(&self) -> Vec<char> {
+        self.args
+            .iter()
+            .flatten()
+            .filter_map(|piece| match piece {
+                Piece::FieldCode(code) => Some(*code),
+                Piece::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Substitute the field codes, giving the program and its arguments.
+    /// %i and %c come from the entry and %k is its path. Only the first
+    /// file or URL is used for %f and %u, see instances().
+    pub fn argv(&self, entry: &ApplicationEntry, files: &[&str], urls: &[&str]) -> Vec<String> {
+        let mut argv = Vec::new();
+
+        for arg in &self.args {
+            match arg.as_slice() {
+                [Piece::FieldCode(code)] => argv.extend(expand_standalone(*code, entry, files, urls)),
+                pieces => {
+                    let mut value = String::new();
+                    for piece in pieces {
+                        match piece {
+                            Piece::Text(text) => value.push_str(text),
+                            Piece::FieldCode(code) => {
+                                value.push_str(&expand_standalone(*code, entry, files, urls).join(" "))
+                            }
+                        }
+                    }
+                    argv.push(value);
+                }
+            }
+        }
+
+        argv
+    }
+
+    /// The argv of every instance to launch. With %f or %u the spec asks
+    /// for one instance per file or URL, otherwise there is a single one.
+    pub fn instances(&self, entry: &ApplicationEntry, files: &[&str], urls: &[&str]) -> Vec<Vec<String>> {
+        let codes = self.field_codes();
+
+        if codes.contains(&'f') && files.len() > 1 {
+            files.iter().map(|file| self.argv(entry, &[file], urls)).collect()
+        } else if codes.contains(&'u') && urls.len() > 1 {
+            urls.iter().map(|url| self.argv(entry, files, &[url])).collect()
+        } else {
+            vec![self.argv(entry, files, urls)]
+        }
+    }
+}
+
+/// A % inside quotes, only %% is allowed there
+fn quoted_percent(chars: &mut std::str::Chars, text: &mut String) -> Result<(), ExecError> {
+    match chars.next() {
+        Some('%') => {
+            text.push('%');
+            Ok(())
+        }
+        Some(code) => Err(ExecError::FieldCodeInQuotes(format!("%{}", code))),
+        None => Err(ExecError::UnterminatedQuote),
+    }
+}
+
+/// End the text of an argument, keeping quoted empty arguments like ""
+fn finish_text(pieces: &mut Vec<Piece>, text: &mut String) {
+    if !text.is_empty() || pieces.is_empty() {
+        pieces.push(Piece::Text(std::mem::take(text)));
+    }
+}
+
+/// The arguments a field code expands to when it is an argument by
+/// itself. Codes with no value are removed.
+fn expand_standalone(code: char, entry: &ApplicationEntry, files: &[&str], urls: &[&str]) -> Vec<String> {
+    let path = entry.path();
+
+    match code {
+        'f' => files.first().map(|f| f.to_string()).into_iter().collect(),
+        'F' => files.iter().map(|f| f.to_string()).collect(),
+        'u' => urls.first().map(|u| u.to_string()).into_iter().collect(),
+        'U' => urls.iter().map(|u| u.to_string()).collect(),
+        'i' => entry
+            .icon()
+            .map(|icon| vec!["--icon".to_string(), icon])
+            .unwrap_or_default(),
+        'c' => entry
+            .get_localized_string("Name", Info::current_locale().as_deref())
+            .into_iter()
+            .collect(),
+        // Entries parsed without a path have no location
+        'k' if !path.as_os_str().is_empty() => vec![path.to_string_lossy().into_owned()],
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use crate::parser::{DesktopEntry, DesktopEntryGroup};
+
+/// Read access to any group of a desktop file, such as [Desktop Entry],
+/// [Desktop Action new-window] or vendor groups like [X-Flatpak].
+///
+/// Keys the spec defines are typed by its schema. Other keys, including
+/// all keys of vendor groups, are read as whatever type is asked for.
+#[derive(Debug, Clone, Copy)]
+pub struct EntryGroup<'a> {
+    group: DesktopEntryGroup<'a>,
+}
+
+impl<'a> EntryGroup<'a> {
+    pub fn name(&self) -> &'a str {
+        self.group.name()
+    }
+
+    /// Keys in file order, without locale suffixes and repeats
+    pub fn keys(&self) -> Vec<&'a str> {
+        let mut keys: Vec<&'a str> = Vec::new();
+        for (key, _) in self.group.raw().entries() {
+            let base = key.split('[').next().unwrap_or(key);
+            if !keys.contains(&base) && self.has_key(base) {
+                keys.push(base);
+            }
+        }
+        keys
+    }
+
+    pub fn has_key(&self, key: &str) -> bool {
+        self.group.get_field(key).is_some() || self.group.translations(key).next().is_some()
+    }
+
+    /// The value as written, with escape sequences intact. Accepts
+    /// localized keys like "Name[de]".
+    pub fn get_raw(&self, key: &str) -> Option<&'a str> {
+        self.group.raw().get(key).map(|value| value.raw())
+    }
+
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.group.get_field(key).and_then(|value| value.as_string())
+    }
+
+    /// Get a localized string, falling back like the spec describes
+    pub fn get_localized_string(&self, key: &str, locale: Option<&str>) -> Option<String> {
+        self.group
+            .get_localized_field(key, locale)
+            .and_then(|value| value.as_string())
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.group.get_field(key).and_then(|value| value.as_bool())
+    }
+
+    pub fn get_numeric(&self, key: &str) -> Option<f64> {
+        self.group.get_field(key).and_then(|value| value.as_numeric())
+    }
+
+    pub fn get_vec(&self, key: &str) -> Option<Vec<String>> {
+        self.group.get_field(key).and_then(|value| value.as_list())
+    }
+
+    /// Every translation of a string key, by locale
+    pub fn translations(&self, key: &str) -> BTreeMap<String, String> {
+        self.group
+            .translations(key)
+            .filter_map(|(locale, value)| Some((locale, value.as_string()?)))
+            .collect()
+    }
+
+    /// Every translation of a list key such as Keywords, by locale
+    pub fn list_translations(&self, key: &str) -> BTreeMap<String, Vec<String>> {
+        self.group
+            .translations(key)
+            .filter_map(|(locale, value)| Some((locale, value.as_list()?)))
+            .collect()
+    }
+}
+
+impl DesktopEntry {
+    pub(crate) fn group(&self, name: &str) -> Option<EntryGroup<'_>> {
+        self.get_group(name).map(|group| EntryGroup { group })
+    }
+
+    /// Group names in file order
+    pub(crate) fn group_names(&self) -> Vec<&str> {
+        self.get_groups().map(|group| group.name()).collect()
+    }
+}
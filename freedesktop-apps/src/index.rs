@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::parser::ParseOptions;
+use crate::{application_entry_paths, scan_entry_files, ApplicationEntry, Entry, FindError, ParseError};
+
+/// A desktop file found while indexing, along with the
+/// files it shadows in less important data dirs
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    id: String,
+    path: PathBuf,
+    shadowed: Vec<PathBuf>,
+}
+
+impl IndexedFile {
+    /// The desktop file ID, e.g. "kde4-foo.desktop"
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The file that provides this ID
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Files with the same ID in less important dirs, which are ignored
+    pub fn shadowed(&self) -> &[PathBuf] {
+        &self.shadowed
+    }
+
+    /// Parse the file, giving applications this file's ID
+    fn load(&self, options: &ParseOptions) -> Result<Entry, ParseError> {
+        match Entry::from_path_with_options(&self.path, options)? {
+            Entry::Application(app) => Ok(Entry::Application(app.with_id(&self.id))),
+            entry => Ok(entry),
+        }
+    }
+}
+
+/// Maps desktop file IDs to files following the Desktop Entry Specification.
+///
+/// Every applications dir is searched recursively and IDs are made from the
+/// path relative to it, with '/' turned into '-'. When several dirs contain
+/// the same ID the first one in XDG order ($XDG_DATA_HOME, then
+/// $XDG_DATA_DIRS) wins, so a user file with Hidden=true masks system entries.
+#[derive(Debug, Clone, Default)]
+pub struct DesktopFileIndex {
+    files: BTreeMap<String, IndexedFile>,
+    errors: Vec<(PathBuf, ParseError)>,
+}
+
+impl DesktopFileIndex {
+    /// Index the applications dirs of the XDG data dirs
+    pub fn load() -> Self {
+        Self::from_dirs(&application_entry_paths())
+    }
+
+    /// Index applications dirs, most important first
+    pub fn from_dirs(dirs: &[PathBuf]) -> Self {
+        let mut files: BTreeMap<String, IndexedFile> = BTreeMap::new();
+        let mut errors = Vec::new();
+
+        for dir in dirs {
+            let (dir_files, mut dir_errors) = scan_entry_files(dir, "desktop");
+            errors.append(&mut dir_errors);
+
+            for (id, path) in dir_files {
+                match files.get_mut(&id) {
+                    Some(existing) => existing.shadowed.push(path),
+                    None => {
+                        files.insert(
+                            id.clone(),
+                            IndexedFile {
+                                id,
+                                path,
+                                shadowed: Vec::new(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        Self { files, errors }
+    }
+
+    /// Directories that couldn't be read while indexing
+    pub fn errors(&self) -> &[(PathBuf, ParseError)] {
+        &self.errors
+    }
+
+    /// All indexed files, sorted by ID. Includes files that fail
+    /// to parse and Hidden=true files that mask an ID.
+    pub fn files(&self) -> impl Iterator<Item = &IndexedFile> {
+        self.files.values()
+    }
+
+    /// All indexed IDs, sorted
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(|id| id.as_str())
+    }
+
+    /// Look up the file for an ID, with or without the .desktop suffix
+    pub fn get(&self, id: &str) -> Option<&IndexedFile> {
+        match id.strip_suffix(".desktop") {
+            Some(_) => self.files.get(id),
+            None => self.files.get(&format!("{}.desktop", id)),
+        }
+    }
+
+    /// Load the application with an ID. IDs masked by Hidden=true are not found.
+    ///
+    /// Installed files are parsed leniently, like desktop environments do,
+    /// so an entry with a stray invalid line still loads and reports it in
+    /// ApplicationEntry::warnings(). Use find_with_options() to be strict.
+    pub fn find(&self, id: &str) -> Result<ApplicationEntry, FindError> {
+        self.find_with_options(id, &ParseOptions::lenient())
+    }
+
+    /// Same as find() but parses the file with the given options. Files
+    /// of other types, such as links, are not found.
+    pub fn find_with_options(&self, id: &str, options: &ParseOptions) -> Result<ApplicationEntry, FindError> {
+        match self.find_entry_with_options(id, options)? {
+            Entry::Application(app) => Ok(app),
+            _ => Err(FindError::NotFound(format!("Desktop entry {} is not an application", id))),
+        }
+    }
+
+    /// Load the application or link with an ID, parsing leniently like find().
+    /// IDs masked by Hidden=true are not found.
+    pub fn find_entry(&self, id: &str) -> Result<Entry, FindError> {
+        self.find_entry_with_options(id, &ParseOptions::lenient())
+    }
+
+    /// Same as find_entry() but parses the file with the given options
+    pub fn find_entry_with_options(&self, id: &str, options: &ParseOptions) -> Result<Entry, FindError> {
+        let file = self
+            .get(id)
+            .ok_or_else(|| FindError::NotFound(format!("No desktop entry found for ID: {}", id)))?;
+
+        let entry = file
+            .load(options)
+            .map_err(|e| FindError::InvalidEntry(file.path.clone(), e))?;
+        if entry.is_hidden() {
+            return Err(FindError::NotFound(format!("Desktop entry {} is hidden", file.id)));
+        }
+
+        Ok(entry)
+    }
+
+    /// Load every application, skipping files that fail to parse
+    /// leniently, Hidden=true entries and entries of other types
+    pub fn applications(&self) -> Vec<ApplicationEntry> {
+        self.applications_with_options(&ParseOptions::lenient())
+    }
+
+    /// Same as applications() but parses the files with the given options
+    pub fn applications_with_options(&self, options: &ParseOptions) -> Vec<ApplicationEntry> {
+        self.entries_with_options(options)
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::Application(app) => Some(app),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Load every entry, skipping files that fail to parse even
+    /// leniently and Hidden=true entries
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries_with_options(&ParseOptions::lenient())
+    }
+
+    /// Same as entries() but parses the files with the given options
+    pub fn entries_with_options(&self, options: &ParseOptions) -> Vec<Entry> {
+        self.files
+            .values()
+            .filter_map(|file| file.load(options).ok())
+            .filter(|entry| !entry.is_hidden() && entry.as_directory().is_none())
+            .collect()
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{ApplicationEntry, ExecuteError};
+
+const DESKTOP_FILE_VAR: &str = "GIO_LAUNCHED_DESKTOP_FILE";
+const DESKTOP_FILE_PID_VAR: &str = "GIO_LAUNCHED_DESKTOP_FILE_PID";
+
+/// Distinguishes startup IDs of launches within the same second
+static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// How to launch an application: its environment, the display to show up
+/// on and the token used for startup notification and focus stealing
+/// prevention.
+///
+/// ```no_run
+/// use freedesktop_apps::{ApplicationEntry, LaunchContext};
+///
+/// let app = ApplicationEntry::from_id("org.gnome.Calculator").unwrap();
+/// let context = LaunchContext::new()
+///     .wayland_display("wayland-1")
+///     .env("GDK_BACKEND", "wayland")
+///     .remove_env("DISPLAY");
+/// app.execute_with_context(&[], &[], &context).unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct LaunchContext {
+    env: Vec<(String, String)>,
+    removed_env: Vec<String>,
+    wayland_display: Option<String>,
+    display: Option<String>,
+    activation_token: Option<String>,
+    timestamp: u32,
+    grace_period: Option<Duration>,
+}
+
+impl LaunchContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an environment variable for the application
+    pub fn env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Don't pass an environment variable on to the application
+    pub fn remove_env<K: Into<String>>(mut self, key: K) -> Self {
+        self.removed_env.push(key.into());
+        self
+    }
+
+    /// Launch onto another Wayland compositor, e.g. a nested one
+    pub fn wayland_display<S: Into<String>>(mut self, display: S) -> Self {
+        self.wayland_display = Some(display.into());
+        self
+    }
+
+    /// Launch onto another X11 display
+    pub fn display<S: Into<String>>(mut self, display: S) -> Self {
+        self.display = Some(display.into());
+        self
+    }
+
+    /// Use a token from the compositor's xdg-activation protocol for
+    /// applications with StartupNotify=true. Compositors only let windows
+    /// take focus with tokens they handed out, so without one an ID is
+    /// generated that only works for X11 startup notification.
+    pub fn activation_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.activation_token = Some(token.into());
+        self
+    }
+
+    /// Time of the user event that caused the launch, used in generated
+    /// X11 startup IDs
+    pub fn timestamp(mut self, timestamp: u32) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Wait this long after launching to catch applications that fail
+    /// right away, e.g. because a library is missing. How they exited is
+    /// kept in LaunchedApp::early_exit(), with the details in their log file.
+    pub fn grace_period(mut self, period: Duration) -> Self {
+        self.grace_period = Some(period);
+        self
+    }
+
+    /// The environment to launch an application with, starting from the
+    /// environment of this process
+    pub(crate) fn environment(&self, entry: &ApplicationEntry) -> LaunchEnvironment {
+        let mut vars: BTreeMap<OsString, OsString> = std::env::vars_os().collect();
+
+        // Tokens and desktop files handed to this process belong to its own launch
+        for key in ["DESKTOP_STARTUP_ID", "XDG_ACTIVATION_TOKEN", DESKTOP_FILE_VAR, DESKTOP_FILE_PID_VAR] {
+            vars.remove(&OsString::from(key));
+        }
+
+        if let Some(display) = &self.wayland_display {
+            vars.insert("WAYLAND_DISPLAY".into(), display.into());
+        }
+        if let Some(display) = &self.display {
+            vars.insert("DISPLAY".into(), display.into());
+        }
+
+        if entry.startup_notify() {
+            let id = self
+                .activation_token
+                .clone()
+                .unwrap_or_else(|| self.startup_id(entry));
+            vars.insert("DESKTOP_STARTUP_ID".into(), id.clone().into());
+            vars.insert("XDG_ACTIVATION_TOKEN".into(), id.into());
+        }
+
+        // Entries parsed without a path have no desktop file to point to
+        let has_desktop_file = !entry.path().as_os_str().is_empty();
+        if has_desktop_file {
+            vars.insert(DESKTOP_FILE_VAR.into(), entry.path().into());
+        }
+
+        for (key, value) in &self.env {
+            vars.insert(key.into(), value.into());
+        }
+        for key in &self.removed_env {
+            vars.remove(&OsString::from(key));
+        }
+
+        let pid_overridden = self
+            .env
+            .iter()
+            .map(|(key, _)| key)
+            .chain(&self.removed_env)
+            .any(|key| key == DESKTOP_FILE_PID_VAR);
+        let desktop_file_pid = has_desktop_file && !pid_overridden;
+
+        LaunchEnvironment {
+            vars,
+            desktop_file_pid,
+            desktop_id: entry.id(),
+        }
+    }
+
+    /// Wait for the grace period, if any, recording the exit status of
+    /// the processes that exit before it is over
+    pub(crate) fn check_launched(&self, launched: &mut [LaunchedApp]) {
+        let Some(period) = self.grace_period else {
+            return;
+        };
+        let deadline = Instant::now() + period;
+
+        loop {
+            let mut running = false;
+            for app in launched.iter_mut() {
+                match app.exit_status() {
+                    Some(status) => app.early_exit = Some(status),
+                    None => running = true,
+                }
+            }
+
+            let now = Instant::now();
+            if !running || now >= deadline {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10).min(deadline - now));
+        }
+    }
+
+    /// A startup ID as described by the Startup Notification Protocol,
+    /// unique to this launch
+    fn startup_id(&self, entry: &ApplicationEntry) -> String {
+        let launcher = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "freedesktop-apps".to_string());
+        let app = entry.id().unwrap_or_else(|| "app".to_string());
+        let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+
+        format!("{}-{}-{}-{}_TIME{}", launcher, std::process::id(), app, sequence, self.timestamp)
+            .replace(char::is_whitespace, "_")
+    }
+}
+
+/// The environment of a single launch, see LaunchContext::environment()
+pub(crate) struct LaunchEnvironment {
+    vars: BTreeMap<OsString, OsString>,
+    /// Whether GIO_LAUNCHED_DESKTOP_FILE_PID is set in the child
+    desktop_file_pid: bool,
+    desktop_id: Option<String>,
+}
+
+impl LaunchEnvironment {
+    /// Give cmd this environment and send its output to the log file of
+    /// the application, returning the log file used. Input is always
+    /// detached from the launcher.
+    pub(crate) fn apply(&self, cmd: &mut Command, program: &str, args: &[String]) -> std::io::Result<Option<PathBuf>> {
+        cmd.env_clear().envs(&self.vars);
+
+        let name = self.desktop_id.clone().unwrap_or_else(|| {
+            Path::new(program)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "app".to_string())
+        });
+        let log_path = log_dir().join(format!("{}.log", name));
+
+        // Logging is best effort, output is discarded if the file can't be opened
+        let log = open_log(&log_path).and_then(|log| Some((log.try_clone().ok()?, log)));
+        let (stdout, stderr, log_path) = match log {
+            Some((stdout, stderr)) => (stdout.into(), stderr.into(), Some(log_path)),
+            None => (Stdio::null(), Stdio::null(), None),
+        };
+        cmd.stdin(Stdio::null()).stdout(stdout).stderr(stderr);
+
+        #[cfg(unix)]
+        if self.desktop_file_pid {
+            self.exec_with_pid(cmd, program, args)?;
+        }
+        #[cfg(not(unix))]
+        let _ = args;
+
+        Ok(log_path)
+    }
+
+    pub(crate) fn desktop_id(&self) -> Option<String> {
+        self.desktop_id.clone()
+    }
+
+    /// GIO_LAUNCHED_DESKTOP_FILE_PID is the PID of the launched process,
+    /// which is only known after fork. Like GLib, space for it is reserved
+    /// in the environment and filled in by the child before it execs the
+    /// program itself, as the child must not allocate. The program is
+    /// looked up in the PATH of the launched environment like execvpe(),
+    /// trying every candidate built up front.
+    #[cfg(unix)]
+    fn exec_with_pid(&self, cmd: &mut Command, program: &str, args: &[String]) -> std::io::Result<()> {
+        use std::ffi::CString;
+        use std::io::{Error, ErrorKind};
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+        use std::os::unix::process::CommandExt;
+
+        let invalid = |_| Error::new(ErrorKind::InvalidInput, "nul byte in command or environment");
+
+        let candidates: Vec<CString> = if program.contains('/') {
+            vec![CString::new(program).map_err(invalid)?]
+        } else {
+            let path_var = self
+                .vars
+                .get(std::ffi::OsStr::new("PATH"))
+                .cloned()
+                .unwrap_or_else(|| "/usr/local/bin:/usr/bin:/bin".into());
+            std::env::split_paths(&path_var)
+                .map(|dir| CString::new(dir.join(program).into_os_string().into_vec()).map_err(invalid))
+                .collect::<std::io::Result<_>>()?
+        };
+
+        let mut strings = Vec::new();
+        for arg in std::iter::once(program).chain(args.iter().map(String::as_str)) {
+            strings.push(CString::new(arg).map_err(invalid)?);
+        }
+        for (key, value) in &self.vars {
+            let mut var = key.as_bytes().to_vec();
+            var.push(b'=');
+            var.extend_from_slice(value.as_bytes());
+            strings.push(CString::new(var).map_err(invalid)?);
+        }
+
+        // Room for the digits of any PID and the terminating nul
+        let prefix = format!("{}=", DESKTOP_FILE_PID_VAR);
+        let mut pid_var = prefix.clone().into_bytes();
+        pid_var.resize(prefix.len() + 21, 0);
+
+        let argc = args.len() + 1;
+        let mut exec = ChildExec {
+            paths: candidates.iter().map(|s| s.as_ptr()).collect(),
+            argv: strings[..argc].iter().map(|s| s.as_ptr()).chain([std::ptr::null()]).collect(),
+            envp: strings[argc..]
+                .iter()
+                .map(|s| s.as_ptr())
+                .chain([pid_var.as_ptr().cast(), std::ptr::null()])
+                .collect(),
+            pid_digits: unsafe { pid_var.as_mut_ptr().add(prefix.len()) },
+            _candidates: candidates,
+            _strings: strings,
+            _pid_var: pid_var,
+        };
+
+        unsafe {
+            cmd.pre_exec(move || {
+                exec.write_pid(libc::getpid() as u32);
+                exec.exec()
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Where the output of launched applications goes, one file per application
+pub fn log_dir() -> PathBuf {
+    freedesktop_core::xdg_state_home().join("freedesktop-apps").join("logs")
+}
+
+fn open_log(path: &Path) -> Option<File> {
+    std::fs::create_dir_all(path.parent()?).ok()?;
+    OpenOptions::new().create(true).append(true).open(path).ok()
+}
+
+/// A launched application process. Dropping it leaves the process running.
+#[derive(Debug)]
+pub struct LaunchedApp {
+    child: Child,
+    process_group: Option<u32>,
+    desktop_id: Option<String>,
+    program: String,
+    log_path: Option<PathBuf>,
+    exit_status: Option<ExitStatus>,
+    early_exit: Option<ExitStatus>,
+}
+
+impl LaunchedApp {
+    pub(crate) fn new(child: Child, program: &str, desktop_id: Option<String>, log_path: Option<PathBuf>) -> Self {
+        // The child has called setpgid() before exec, which spawn() waits for
+        #[cfg(unix)]
+        let process_group = match unsafe { libc::getpgid(child.id() as libc::pid_t) } {
+            -1 => None,
+            group => Some(group as u32),
+        };
+        #[cfg(not(unix))]
+        let process_group = None;
+
+        Self {
+            child,
+            process_group,
+            desktop_id,
+            program: program.to_string(),
+            log_path,
+            exit_status: None,
+            early_exit: None,
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// The process group the application runs in, which is its own
+    pub fn process_group(&self) -> Option<u32> {
+        self.process_group
+    }
+
+    /// Desktop file ID of the launched application, e.g. "org.gnome.Calculator"
+    pub fn desktop_id(&self) -> Option<&str> {
+        self.desktop_id.as_deref()
+    }
+
+    /// The file stdout and stderr of the application are appended to
+    pub fn log_path(&self) -> Option<&Path> {
+        self.log_path.as_deref()
+    }
+
+    /// The exit status if the process has exited, without waiting for it
+    pub fn exit_status(&mut self) -> Option<ExitStatus> {
+        if self.exit_status.is_none() {
+            self.exit_status = self.child.try_wait().ok().flatten();
+        }
+        self.exit_status
+    }
+
+    /// Wait for the process to exit
+    pub fn wait(&mut self) -> std::io::Result<ExitStatus> {
+        if let Some(status) = self.exit_status {
+            return Ok(status);
+        }
+        let status = self.child.wait()?;
+        self.exit_status = Some(status);
+        Ok(status)
+    }
+
+    pub fn into_child(self) -> Child {
+        self.child
+    }
+
+    /// How the process exited within the grace period of its LaunchContext.
+    /// None if it was still running at the end, or there was no grace period.
+    pub fn early_exit(&self) -> Option<ExitStatus> {
+        self.early_exit
+    }
+
+    /// ExecuteError::LaunchFailed if the process exited unsuccessfully
+    /// within the grace period, pointing at its log file
+    pub fn launch_error(&self) -> Option<ExecuteError> {
+        let status = self.early_exit.filter(|status| !status.success())?;
+        let name = self.desktop_id.as_deref().unwrap_or(&self.program);
+        let mut message = format!("{} exited right away with {}", name, status);
+        if let Some(log_path) = &self.log_path {
+            message.push_str(&format!(", see {}", log_path.display()));
+        }
+        Some(ExecuteError::LaunchFailed(message))
+    }
+}
+
+/// Everything the child needs to exec, allocated before fork. The
+/// pointers point into the owned strings, whose buffers don't move.
+#[cfg(unix)]
+struct ChildExec {
+    /// The program, or every place in PATH it may be
+    paths: Vec<*const libc::c_char>,
+    argv: Vec<*const libc::c_char>,
+    envp: Vec<*const libc::c_char>,
+    pid_digits: *mut u8,
+    _candidates: Vec<std::ffi::CString>,
+    _strings: Vec<std::ffi::CString>,
+    _pid_var: Vec<u8>,
+}
+
+// The pointers are only used in the forked child
+#[cfg(unix)]
+unsafe impl Send for ChildExec {}
+#[cfg(unix)]
+unsafe impl Sync for ChildExec {}
+
+#[cfg(unix)]
+impl ChildExec {
+    /// Exec the first candidate that can be run. Like execvpe(), missing
+    /// files are skipped and EACCES is only reported if nothing else runs.
+    fn exec(&self) -> std::io::Result<()> {
+        let mut denied = false;
+
+        for path in &self.paths {
+            unsafe { libc::execve(*path, self.argv.as_ptr(), self.envp.as_ptr()) };
+
+            let error = std::io::Error::last_os_error();
+            match error.raw_os_error() {
+                Some(libc::EACCES) => denied = true,
+                Some(libc::ENOENT | libc::ENOTDIR) => {}
+                _ => return Err(error),
+            }
+        }
+
+        Err(std::io::Error::from_raw_os_error(if denied { libc::EACCES } else { libc::ENOENT }))
+    }
+
+    /// Write the PID into the reserved space without allocating
+    fn write_pid(&mut self, mut pid: u32) {
+        let mut digits = [0u8; 10];
+        let mut len = 0;
+        loop {
+            digits[len] = b'0' + (pid % 10) as u8;
+            pid /= 10;
+            len += 1;
+            if pid == 0 {
+                break;
+            }
+        }
+
+        for (i, digit) in digits[..len].iter().rev().enumerate() {
+            unsafe { *self.pid_digits.add(i) = *digit };
+        }
+        unsafe { *self.pid_digits.add(len) = 0 };
+    }
+}
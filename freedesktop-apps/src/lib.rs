@@ -1,16 +1,62 @@
-use std::path::{Path, PathBuf};
-
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use freedesktop_core::info::Info;
+
+mod action;
+mod borrowed;
+mod directory;
+mod document;
+mod edit;
+mod email;
+mod entry;
+mod exec;
+mod group;
+mod index;
+mod launch;
+mod link;
+mod load;
+mod menu;
+mod mime;
 mod parser;
-use parser::{DesktopEntry, ValueType};
+mod schema;
+mod validate;
+use launch::LaunchEnvironment;
+use parser::DesktopEntry;
+
+pub use action::DesktopAction;
+pub use borrowed::{BorrowedDesktopFile, BorrowedGroup, RawValue};
+pub use directory::{directory_entry_paths, DirectoryEntry};
+pub use document::{escape_list, escape_value, DesktopFile};
+pub use edit::{user_override_path, EditError};
+pub use email::{Email, MailClient};
+pub use entry::Entry;
+pub use exec::{ExecCommand, ExecError};
+pub use group::EntryGroup;
+pub use index::{DesktopFileIndex, IndexedFile};
+pub use launch::{log_dir, LaunchContext, LaunchedApp};
+pub use link::LinkEntry;
+pub use load::{FilterReason, LoadReport};
+pub use menu::{
+    menu_file_path, user_edits_path, LayoutAttributes, LayoutNode, Menu, MenuEditor, MenuError,
+    MenuItem, MergeType,
+};
+pub use mime::{mime_type_for_path, mimeapps_list_paths, xdg_terminals_list_paths, MimeApps, MimeAppsList};
 
 // Re-export the ParseError from parser
-pub use parser::ParseError;
+pub use parser::{ParseError, ParseOptions};
+pub use schema::{key_type, KeyType};
+pub use validate::{validate, validate_path, Diagnostic, Severity};
 
 #[derive(Debug)]
 pub enum FindError {
-    NotFound(String),        // Desktop entry ID not found
-    ParseError(ParseError),  // Failed to parse the desktop file
-    IoError(std::io::Error), // IO error during search
+    NotFound(String),                 // Desktop entry ID not found
+    ParseError(ParseError),           // Failed to parse the desktop file
+    InvalidEntry(PathBuf, ParseError), // The file for the ID exists but failed to parse
+    IoError(std::io::Error),          // IO error during search
 }
 
 impl std::fmt::Display for FindError {
@@ -18,6 +64,9 @@ impl std::fmt::Display for FindError {
         match self {
             FindError::NotFound(msg) => write!(f, "Desktop entry not found: {}", msg),
             FindError::ParseError(err) => write!(f, "Parse error: {}", err),
+            FindError::InvalidEntry(path, err) => {
+                write!(f, "Invalid desktop entry {}: {}", path.display(), err)
+            }
             FindError::IoError(err) => write!(f, "IO error: {}", err),
         }
     }
@@ -37,13 +86,20 @@ impl From<std::io::Error> for FindError {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum ExecuteError {
     NotExecutable(String),
     TerminalNotFound,
     InvalidCommand(String),
     IoError(String),
     ValidationFailed(String),
+    NoHandler(String),
+    /// The application exited unsuccessfully within the grace period,
+    /// see LaunchedApp::launch_error()
+    LaunchFailed(String),
+    /// Starting one of several instances failed. The instances started
+    /// before it keep running and are handed back with the error.
+    PartialLaunch(Vec<LaunchedApp>, Box<ExecuteError>),
 }
 
 impl std::fmt::Display for ExecuteError {
@@ -54,24 +110,126 @@ impl std::fmt::Display for ExecuteError {
             ExecuteError::InvalidCommand(msg) => write!(f, "Invalid command: {}", msg),
             ExecuteError::IoError(msg) => write!(f, "I/O error: {}", msg),
             ExecuteError::ValidationFailed(msg) => write!(f, "Validation failed: {}", msg),
+            ExecuteError::NoHandler(msg) => write!(f, "No application found to handle: {}", msg),
+            ExecuteError::LaunchFailed(msg) => write!(f, "Launch failed: {}", msg),
+            ExecuteError::PartialLaunch(launched, err) => {
+                write!(f, "Launch failed after starting {} instance(s): {}", launched.len(), err)
+            }
         }
     }
 }
 
 impl std::error::Error for ExecuteError {}
 
+/// $XDG_DATA_HOME followed by $XDG_DATA_DIRS, most important first
+pub(crate) fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![freedesktop_core::xdg_data_home()];
+    dirs.extend(freedesktop_core::xdg_data_dirs());
+    dirs
+}
+
+/// The OnlyShowIn/NotShowIn rules of the spec: the first desktop that appears
+/// in either list decides. If none does, the entry is shown unless it has
+/// OnlyShowIn. Desktop names are compared case-sensitively.
+pub(crate) fn show_in<S: AsRef<str>>(
+    only_show_in: &Option<Vec<String>>,
+    not_show_in: &Option<Vec<String>>,
+    desktops: &[S],
+) -> bool {
+    let listed = |list: &Option<Vec<String>>, desktop: &str| {
+        list.as_ref().is_some_and(|l| l.iter().any(|d| d == desktop))
+    };
+
+    for desktop in desktops {
+        let desktop = desktop.as_ref();
+        if listed(only_show_in, desktop) {
+            return true;
+        }
+        if listed(not_show_in, desktop) {
+            return false;
+        }
+    }
+
+    only_show_in.is_none()
+}
+
+/// The applications dirs of $XDG_DATA_HOME and $XDG_DATA_DIRS,
+/// most important first
 pub fn application_entry_paths() -> Vec<PathBuf> {
-    freedesktop_core::base_directories()
+    data_dirs()
         .iter()
         .map(|path| path.join("applications"))
         .filter(|path| path.exists())
         .collect()
 }
 
-#[derive(Debug)]
+/// Recursively collect the .desktop files in a directory along with their
+/// desktop file IDs. The ID is the path relative to `dir` with '/' turned
+/// into '-', e.g. <dir>/kde4/foo.desktop → kde4-foo.desktop
+pub(crate) fn desktop_files_in(dir: &Path) -> Vec<(String, PathBuf)> {
+    entry_files_in(dir, "desktop")
+}
+
+/// Like desktop_files_in, for files with any extension
+pub(crate) fn entry_files_in(dir: &Path, extension: &str) -> Vec<(String, PathBuf)> {
+    scan_entry_files(dir, extension).0
+}
+
+/// Desktop file IDs and the files providing them
+type EntryFiles = Vec<(String, PathBuf)>;
+
+/// Like entry_files_in, also returning the directories that couldn't be read
+pub(crate) fn scan_entry_files(dir: &Path, extension: &str) -> (EntryFiles, Vec<(PathBuf, ParseError)>) {
+    let mut files: EntryFiles = Vec::new();
+    let mut errors: Vec<(PathBuf, ParseError)> = Vec::new();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut pending: Vec<PathBuf> = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        // Guard against symlink loops
+        let canonical = match current.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(e) => {
+                errors.push((current, ParseError::IoError(format!("Failed to read directory: {}", e))));
+                continue;
+            }
+        };
+        if !visited.insert(canonical) {
+            continue;
+        }
+
+        let dir_entries = match std::fs::read_dir(&current) {
+            Ok(dir_entries) => dir_entries,
+            Err(e) => {
+                errors.push((current, ParseError::IoError(format!("Failed to read directory: {}", e))));
+                continue;
+            }
+        };
+
+        for entry in dir_entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == extension) {
+                if let Ok(relative) = path.strip_prefix(dir) {
+                    let id = relative.to_string_lossy().replace('/', "-");
+                    files.push((id, path));
+                }
+            }
+        }
+    }
+
+    files.sort();
+    (files, errors)
+}
+
+#[derive(Debug, Clone)]
 #[derive(Default)]
 pub struct ApplicationEntry {
     inner: DesktopEntry,
+    /// Desktop file ID when the entry was found through the index
+    id: Option<String>,
 }
 
 
@@ -87,7 +245,21 @@ impl ApplicationEntry {
     /// XDG_DATA_DIRS component, removing "applications/" prefix, and converting
     /// '/' to '-'. For example: /usr/share/applications/foo/bar.desktop → foo-bar.desktop
     pub fn id(&self) -> Option<String> {
+        if let Some(id) = &self.id {
+            return Some(id.strip_suffix(".desktop").unwrap_or(id).to_string());
+        }
+
         let file_path = &self.inner.path;
+
+        // Relative to the applications dir of a data dir
+        for dir in application_entry_paths() {
+            if let Ok(relative) = file_path.strip_prefix(&dir) {
+                let relative = relative.to_string_lossy();
+                if let Some(desktop_entry_path) = relative.strip_suffix(".desktop") {
+                    return Some(desktop_entry_path.replace('/', "-"));
+                }
+            }
+        }
         
         // Check if this file is within any applications directory
         if let Some(apps_pos) = file_path.to_string_lossy().find("/applications/") {
@@ -118,12 +290,7 @@ impl ApplicationEntry {
         self.inner
             .get_desktop_entry_group()
             .and_then(|group| group.get_field(key))
-            .and_then(|value| match value {
-                ValueType::String(s) | ValueType::LocaleString(s) | ValueType::IconString(s) => {
-                    Some(s.clone())
-                }
-                _ => None,
-            })
+            .and_then(|value| value.as_string())
     }
 
     /// Get a localized string value from the Desktop Entry group
@@ -131,12 +298,7 @@ impl ApplicationEntry {
         self.inner
             .get_desktop_entry_group()
             .and_then(|group| group.get_localized_field(key, locale))
-            .and_then(|value| match value {
-                ValueType::String(s) | ValueType::LocaleString(s) | ValueType::IconString(s) => {
-                    Some(s.clone())
-                }
-                _ => None,
-            })
+            .and_then(|value| value.as_string())
     }
 
     /// Get a boolean value from the Desktop Entry group
@@ -144,10 +306,7 @@ impl ApplicationEntry {
         self.inner
             .get_desktop_entry_group()
             .and_then(|group| group.get_field(key))
-            .and_then(|value| match value {
-                ValueType::Boolean(b) => Some(*b),
-                _ => None,
-            })
+            .and_then(|value| value.as_bool())
     }
 
     /// Get a numeric value from the Desktop Entry group
@@ -155,10 +314,7 @@ impl ApplicationEntry {
         self.inner
             .get_desktop_entry_group()
             .and_then(|group| group.get_field(key))
-            .and_then(|value| match value {
-                ValueType::Numeric(n) => Some(*n),
-                _ => None,
-            })
+            .and_then(|value| value.as_numeric())
     }
 
     /// Get a vector of strings from the Desktop Entry group
@@ -166,12 +322,17 @@ impl ApplicationEntry {
         self.inner
             .get_desktop_entry_group()
             .and_then(|group| group.get_field(key))
-            .and_then(|value| match value {
-                ValueType::StringList(list) | ValueType::LocaleStringList(list) => {
-                    Some(list.clone())
-                }
-                _ => None,
-            })
+            .and_then(|value| value.as_list())
+    }
+
+    /// Any group of the file by name, e.g. "X-Flatpak"
+    pub fn group(&self, name: &str) -> Option<EntryGroup<'_>> {
+        self.inner.group(name)
+    }
+
+    /// Names of all groups, in file order
+    pub fn groups(&self) -> Vec<&str> {
+        self.inner.group_names()
     }
 
     /// Get the file path of this desktop entry
@@ -179,6 +340,16 @@ impl ApplicationEntry {
         &self.inner.path
     }
 
+    /// Problems that lenient parsing skipped over. Always empty for strict parsing.
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.inner.warnings
+    }
+
+    /// The file as it was written, with comments and formatting intact
+    pub fn document(&self) -> &DesktopFile {
+        self.inner.document()
+    }
+
     /// Get the entry type (Application, Link, Directory)
     pub fn entry_type(&self) -> Option<String> {
         self.get_string("Type")
@@ -194,8 +365,21 @@ impl ApplicationEntry {
         self.get_string("Comment")
     }
 
+    /// Check if the entry should be shown in the current desktop's menus,
+    /// taking Hidden, NoDisplay, OnlyShowIn and NotShowIn into account.
+    /// The current desktops come from $XDG_CURRENT_DESKTOP.
     pub fn should_show(&self) -> bool {
-        !self.is_hidden() && !self.no_display()
+        !self.is_hidden() && !self.no_display() && self.should_show_in(&Info::current_desktops())
+    }
+
+    /// Evaluate OnlyShowIn and NotShowIn for a list of desktop
+    /// names, most important first, e.g. ["ubuntu", "GNOME"]
+    pub fn should_show_in<S: AsRef<str>>(&self, desktops: &[S]) -> bool {
+        show_in(
+            &self.get_vec("OnlyShowIn"),
+            &self.get_vec("NotShowIn"),
+            desktops,
+        )
     }
 
     /// Check if entry should be hidden
@@ -203,6 +387,12 @@ impl ApplicationEntry {
         self.get_bool("Hidden").unwrap_or(false)
     }
 
+    /// Check if the TryExec program, if any, is installed and executable.
+    /// Per the spec, entries failing this check should be ignored.
+    pub fn is_available(&self) -> bool {
+        self.validate_try_exec().is_ok()
+    }
+
     /// Check if entry should not be displayed in menus
     pub fn no_display(&self) -> bool {
         self.get_bool("NoDisplay").unwrap_or(false)
@@ -228,66 +418,122 @@ impl ApplicationEntry {
         self.get_bool("Terminal").unwrap_or(false)
     }
 
+    /// Check if the application supports startup notification
+    pub fn startup_notify(&self) -> bool {
+        self.get_bool("StartupNotify").unwrap_or(false)
+    }
+
     /// Get working directory
     pub fn path_dir(&self) -> Option<String> {
         self.get_string("Path")
     }
 
     /// Execute this application with no files
-    pub fn execute(&self) -> Result<(), ExecuteError> {
+    pub fn execute(&self) -> Result<Vec<LaunchedApp>, ExecuteError> {
         self.execute_with_files(&[])
     }
 
-    /// Execute this application with the given files
-    pub fn execute_with_files(&self, files: &[&str]) -> Result<(), ExecuteError> {
+    /// Execute this application with the given files. Applications that
+    /// take a single file (%f) are launched once per file.
+    pub fn execute_with_files(&self, files: &[&str]) -> Result<Vec<LaunchedApp>, ExecuteError> {
         self.execute_internal(files, &[])
     }
 
-    /// Execute this application with the given URLs
-    pub fn execute_with_urls(&self, urls: &[&str]) -> Result<(), ExecuteError> {
+    /// Execute this application with the given URLs. Applications that
+    /// take a single URL (%u) are launched once per URL.
+    pub fn execute_with_urls(&self, urls: &[&str]) -> Result<Vec<LaunchedApp>, ExecuteError> {
         self.execute_internal(&[], urls)
     }
 
-    /// Prepare the command for execution without actually executing it (for testing)
-    pub fn prepare_command(&self, files: &[&str], urls: &[&str]) -> Result<(String, Vec<String>), ExecuteError> {
+    /// Execute this application with files or URLs, launched as the context says
+    pub fn execute_with_context(
+        &self,
+        files: &[&str],
+        urls: &[&str],
+        context: &LaunchContext,
+    ) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        let commands = self.prepare_command(files, urls)?;
+        self.spawn_all(&commands, context)
+    }
+
+    /// Prepare the commands for execution without actually executing them (for testing).
+    /// There is one command per instance to launch.
+    pub fn prepare_command(&self, files: &[&str], urls: &[&str]) -> Result<Vec<(String, Vec<String>)>, ExecuteError> {
         // Validate the application can be executed
         self.validate_executable()?;
 
-        // Get the command and arguments
-        let (program, args) = self.parse_exec_command(files, urls)?;
+        let exec = self.exec().unwrap(); // Already validated in validate_executable
+        self.prepare_exec(&exec, files, urls)
+    }
 
-        // Handle terminal applications
-        let (final_program, final_args) = if self.terminal() {
-            self.wrap_with_terminal(&program, &args)?
-        } else {
-            (program, args)
-        };
+    fn execute_internal(&self, files: &[&str], urls: &[&str]) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.execute_with_context(files, urls, &LaunchContext::default())
+    }
 
-        Ok((final_program, final_args))
+    /// Parse the Exec value of this entry, see ExecCommand
+    pub fn exec_command(&self) -> Result<ExecCommand, ExecuteError> {
+        let exec = self.exec().ok_or_else(|| {
+            ExecuteError::NotExecutable("No Exec key found".to_string())
+        })?;
+        Ok(ExecCommand::parse(&exec)?)
     }
 
-    fn execute_internal(&self, files: &[&str], urls: &[&str]) -> Result<(), ExecuteError> {
-        // Validate the application can be executed
-        self.validate_executable()?;
+    /// Expand the field codes of an Exec value belonging to this entry
+    /// and wrap it in a terminal if the entry asks for one
+    pub(crate) fn prepare_exec(&self, exec: &str, files: &[&str], urls: &[&str]) -> Result<Vec<(String, Vec<String>)>, ExecuteError> {
+        let command = ExecCommand::parse(exec)?;
 
-        // Get the command and arguments
-        let (program, args) = self.parse_exec_command(files, urls)?;
+        command
+            .instances(self, files, urls)
+            .into_iter()
+            .map(|mut args| {
+                let program = args.remove(0);
 
-        // Handle terminal applications
-        let (final_program, final_args) = if self.terminal() {
-            self.wrap_with_terminal(&program, &args)?
-        } else {
-            (program, args)
-        };
+                // Handle terminal applications
+                if self.terminal() {
+                    self.wrap_with_terminal(&program, &args)
+                } else {
+                    Ok((program, args))
+                }
+            })
+            .collect()
+    }
 
+    /// Spawn a prepared command in the working directory of this entry
+    pub(crate) fn spawn(&self, program: &str, args: &[String], context: &LaunchContext) -> Result<LaunchedApp, ExecuteError> {
         // Set working directory if specified
         let working_dir = self.path_dir();
-        
+
         // Spawn the process detached
-        spawn_detached_with_env(&final_program, &final_args, working_dir.as_deref())
+        spawn_detached_with_env(program, args, working_dir.as_deref(), &context.environment(self))
             .map_err(|e| ExecuteError::IoError(format!("Failed to spawn process: {}", e)))
     }
 
+    /// Spawn every prepared command, stopping at the first that fails,
+    /// then watch them for the grace period of the context. When a later
+    /// command fails, the instances already running are returned in
+    /// ExecuteError::PartialLaunch.
+    pub(crate) fn spawn_all(
+        &self,
+        commands: &[(String, Vec<String>)],
+        context: &LaunchContext,
+    ) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        let mut launched = Vec::with_capacity(commands.len());
+        for (program, args) in commands {
+            match self.spawn(program, args, context) {
+                Ok(app) => launched.push(app),
+                Err(err) if launched.is_empty() => return Err(err),
+                Err(err) => {
+                    context.check_launched(&mut launched);
+                    return Err(ExecuteError::PartialLaunch(launched, Box::new(err)));
+                }
+            }
+        }
+
+        context.check_launched(&mut launched);
+        Ok(launched)
+    }
+
     fn validate_executable(&self) -> Result<(), ExecuteError> {
         // Check if we have an Exec key
         let exec = self.exec().ok_or_else(|| {
@@ -298,7 +544,11 @@ impl ApplicationEntry {
             return Err(ExecuteError::NotExecutable("Exec key is empty".to_string()));
         }
 
-        // Check TryExec if present
+        self.validate_try_exec()
+    }
+
+    /// Check TryExec if present
+    pub(crate) fn validate_try_exec(&self) -> Result<(), ExecuteError> {
         if let Some(try_exec) = self.get_string("TryExec") {
             if !is_executable_available(&try_exec) {
                 return Err(ExecuteError::ValidationFailed(
@@ -310,81 +560,6 @@ impl ApplicationEntry {
         Ok(())
     }
 
-    fn parse_exec_command(&self, files: &[&str], urls: &[&str]) -> Result<(String, Vec<String>), ExecuteError> {
-        let exec = self.exec().unwrap(); // Already validated in validate_executable
-        
-        // Expand field codes
-        let expanded = self.expand_field_codes(&exec, files, urls);
-        
-        // Parse the command line
-        parse_command_line(&expanded)
-    }
-
-    fn expand_field_codes(&self, exec: &str, files: &[&str], urls: &[&str]) -> String {
-        let mut result = String::new();
-        let mut chars = exec.chars().peekable();
-
-        while let Some(ch) = chars.next() {
-            if ch == '%' {
-                if let Some(&next_ch) = chars.peek() {
-                    chars.next(); // consume the next character
-                    match next_ch {
-                        '%' => result.push('%'),
-                        'f' => {
-                            if let Some(file) = files.first() {
-                                result.push_str(&shell_escape(file));
-                            }
-                        },
-                        'F' => {
-                            for (i, file) in files.iter().enumerate() {
-                                if i > 0 { result.push(' '); }
-                                result.push_str(&shell_escape(file));
-                            }
-                        },
-                        'u' => {
-                            if let Some(url) = urls.first() {
-                                result.push_str(&shell_escape(url));
-                            }
-                        },
-                        'U' => {
-                            for (i, url) in urls.iter().enumerate() {
-                                if i > 0 { result.push(' '); }
-                                result.push_str(&shell_escape(url));
-                            }
-                        },
-                        'i' => {
-                            if let Some(icon) = self.icon() {
-                                result.push_str("--icon ");
-                                result.push_str(&shell_escape(&icon));
-                            }
-                        },
-                        'c' => {
-                            if let Some(name) = self.name() {
-                                result.push_str(&shell_escape(&name));
-                            }
-                        },
-                        'k' => {
-                            let path = self.path().to_string_lossy();
-                            result.push_str(&shell_escape(&path));
-                        },
-                        // Deprecated field codes - ignore
-                        'd' | 'D' | 'n' | 'N' | 'v' | 'm' => {},
-                        // Unknown field code - this is an error per spec
-                        _ => {
-                            return format!("{}%{}{}", result, next_ch, chars.collect::<String>());
-                        }
-                    }
-                } else {
-                    result.push(ch);
-                }
-            } else {
-                result.push(ch);
-            }
-        }
-
-        result
-    }
-
     fn wrap_with_terminal(&self, program: &str, args: &[String]) -> Result<(String, Vec<String>), ExecuteError> {
         let terminal = find_terminal().ok_or(ExecuteError::TerminalNotFound)?;
         
@@ -398,21 +573,25 @@ impl ApplicationEntry {
 }
 
 impl ApplicationEntry {
-    /// Get all application entries from standard directories
+    /// Get all application entries from standard directories.
+    /// Each desktop file ID is only returned once, see DesktopFileIndex.
     pub fn all() -> Vec<ApplicationEntry> {
-        let mut entries: Vec<ApplicationEntry> = Vec::new();
-        for p in application_entry_paths() {
-            if let Ok(dir_entries) = std::fs::read_dir(p) {
-                for entry in dir_entries.filter_map(|e| e.ok()) {
-                    if entry.path().extension().is_some_and(|ext| ext == "desktop") {
-                        if let Ok(app_entry) = ApplicationEntry::from_path(entry.path()) {
-                            entries.push(app_entry);
-                        }
-                    }
-                }
-            }
-        }
-        entries
+        DesktopFileIndex::load().applications()
+    }
+
+    /// Load all applications like all(), also reporting the files that
+    /// failed to load and why the others aren't shown, see LoadReport
+    pub fn load_all() -> LoadReport {
+        DesktopFileIndex::load().load_applications(&Info::current_desktops())
+    }
+
+    /// Get all application entries whose TryExec program is installed.
+    /// Entries without TryExec are always included.
+    pub fn all_available() -> Vec<ApplicationEntry> {
+        Self::all()
+            .into_iter()
+            .filter(|entry| entry.is_available())
+            .collect()
     }
 
     /// Create an ApplicationEntry from a path
@@ -425,81 +604,101 @@ impl ApplicationEntry {
         let desktop_entry = DesktopEntry::from_path(path)?;
         Ok(ApplicationEntry {
             inner: desktop_entry,
+            id: None,
         })
     }
 
-    /// Find an ApplicationEntry by its desktop file ID
-    /// 
-    /// The desktop file ID follows the freedesktop specification format.
-    /// For example: "foo-bar.desktop" would look for files like:
-    /// - /usr/share/applications/foo/bar.desktop
-    /// - /usr/share/applications/foo-bar.desktop
+    /// Read an entry with strict or lenient parsing, see ParseOptions
+    pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, ParseError> {
+        let desktop_entry = DesktopEntry::from_path_with_options(path, options)?;
+        Ok(ApplicationEntry {
+            inner: desktop_entry,
+            id: None,
+        })
+    }
+
+    /// Parse an entry from the text of a desktop file. The optional path is
+    /// treated as where the entry came from, for its ID and the %k field code.
+    pub fn from_str(content: &str, path: Option<&Path>) -> Result<Self, ParseError> {
+        let desktop_entry = DesktopEntry::parse(content, path.unwrap_or(Path::new("")))?;
+        Ok(ApplicationEntry {
+            inner: desktop_entry,
+            id: None,
+        })
+    }
+
+    /// Parse an entry from the bytes of a desktop file, see from_str()
+    pub fn from_bytes(content: &[u8], path: Option<&Path>) -> Result<Self, ParseError> {
+        let desktop_entry = DesktopEntry::from_bytes(content, path.unwrap_or(Path::new("")))?;
+        Ok(ApplicationEntry {
+            inner: desktop_entry,
+            id: None,
+        })
+    }
+
+    /// Parse an entry from bytes with strict or lenient parsing, see from_str()
+    pub fn from_bytes_with_options(
+        content: &[u8],
+        path: Option<&Path>,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let desktop_entry =
+            DesktopEntry::from_bytes_with_options(content, path.unwrap_or(Path::new("")), options)?;
+        Ok(ApplicationEntry {
+            inner: desktop_entry,
+            id: None,
+        })
+    }
+
+    /// Read and parse an entry, see from_str()
+    pub fn from_reader<R: std::io::Read>(mut reader: R, path: Option<&Path>) -> Result<Self, ParseError> {
+        let mut content = Vec::new();
+        reader
+            .read_to_end(&mut content)
+            .map_err(|e| ParseError::IoError(format!("Failed to read desktop entry: {}", e)))?;
+
+        Self::from_bytes(&content, path)
+    }
+
+    pub(crate) fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Find an ApplicationEntry by its desktop file ID, e.g. "kde4-foo.desktop"
+    /// for applications/kde4/foo.desktop. The ".desktop" suffix is optional.
+    ///
+    /// IDs are computed from the files that exist rather than guessed, so
+    /// "org.gnome.Text-Editor" finds org.gnome.Text-Editor.desktop. The first
+    /// data dir in XDG order providing the ID wins, and Hidden=true there means
+    /// it was deleted. If that file fails to parse, FindError::InvalidEntry is
+    /// returned instead of falling back to a less important dir.
     pub fn from_id(id: &str) -> Result<Self, FindError> {
-        // Convert dashes back to path separators for subdirectories
-        let path_with_slashes = id.replace('-', "/");
-        
-        // Try both the converted path and the original ID as filename
-        let candidates = if path_with_slashes != id {
-            vec![path_with_slashes, id.to_string()]
-        } else {
-            vec![id.to_string()]
-        };
-        
-        for app_dir in application_entry_paths() {
-            for candidate in &candidates {
-                // Ensure .desktop extension
-                let desktop_file = if candidate.ends_with(".desktop") {
-                    candidate.clone()
-                } else {
-                    format!("{}.desktop", candidate)
-                };
-                
-                let full_path = app_dir.join(&desktop_file);
-                if full_path.exists() {
-                    return Self::from_path(&full_path).map_err(FindError::from);
-                }
-            }
-        }
-        
-        Err(FindError::NotFound(format!("No desktop entry found for ID: {}", id)))
+        DesktopFileIndex::load().find(id)
     }
 }
 
-/// Spawn a process completely detached from the current process while preserving display environment
-fn spawn_detached_with_env(program: &str, args: &[String], working_dir: Option<&str>) -> Result<(), std::io::Error> {
-    use std::process::{Command, Stdio};
+/// Spawn a process completely detached from the current process with the environment of its launch,
+/// logging its output
+fn spawn_detached_with_env(
+    program: &str,
+    args: &[String],
+    working_dir: Option<&str>,
+    env: &LaunchEnvironment,
+) -> Result<LaunchedApp, std::io::Error> {
+    use std::process::Command;
     
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+
+    // Set working directory if provided
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
     #[cfg(unix)]
     {
         use std::os::unix::process::CommandExt;
-        
-        let mut cmd = Command::new(program);
-        cmd.args(args)
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
-
-        // Set working directory if provided
-        if let Some(dir) = working_dir {
-            cmd.current_dir(dir);
-        }
-
-        // Explicitly preserve important environment variables
-        if let Ok(wayland_display) = std::env::var("WAYLAND_DISPLAY") {
-            cmd.env("WAYLAND_DISPLAY", wayland_display);
-        }
-        if let Ok(display) = std::env::var("DISPLAY") {
-            cmd.env("DISPLAY", display);
-        }
-        if let Ok(xdg_runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
-            cmd.env("XDG_RUNTIME_DIR", xdg_runtime_dir);
-        }
-        if let Ok(xdg_session_type) = std::env::var("XDG_SESSION_TYPE") {
-            cmd.env("XDG_SESSION_TYPE", xdg_session_type);
-        }
-        if let Ok(xdg_current_desktop) = std::env::var("XDG_CURRENT_DESKTOP") {
-            cmd.env("XDG_CURRENT_DESKTOP", xdg_current_desktop);
-        }
 
         unsafe {
             cmd.pre_exec(|| {
@@ -509,53 +708,74 @@ fn spawn_detached_with_env(program: &str, args: &[String], working_dir: Option<&
                 Ok(())
             });
         }
-
-        cmd.spawn()?;
-        Ok(())
-    }
-    
-    #[cfg(not(unix))]
-    {
-        let mut cmd = Command::new(program);
-        cmd.args(args)
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
-        
-        // Set working directory if provided
-        if let Some(dir) = working_dir {
-            cmd.current_dir(dir);
-        }
-        
-        cmd.spawn()?;
-        Ok(())
     }
+
+    let log_path = env.apply(&mut cmd, program, args)?;
+    let child = cmd.spawn()?;
+    Ok(LaunchedApp::new(child, program, env.desktop_id(), log_path))
 }
 
 /// Check if an executable is available in PATH or as absolute path
 fn is_executable_available(executable: &str) -> bool {
-    use std::path::Path;
-    
-    if Path::new(executable).is_absolute() {
-        // Absolute path - check if file exists and is executable
-        Path::new(executable).exists()
+    if executable.contains('/') {
+        // Absolute or relative path - check the file itself
+        is_executable_file(Path::new(executable))
     } else {
-        // Relative path - check in PATH
+        // Bare name - check in PATH
         which_command(executable).is_some()
     }
 }
 
-/// Find an executable in PATH (simple implementation)
+/// Check that a path is a regular file with an executable bit set
+fn is_executable_file(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+/// Results of PATH lookups, keyed by the value of PATH and the executable name
+type WhichCache = HashMap<(String, String), Option<String>>;
+
+static WHICH_CACHE: Mutex<Option<WhichCache>> = Mutex::new(None);
+
+/// Find an executable in PATH. Results are cached per value of PATH,
+/// use clear_executable_cache() after installing or removing programs.
 fn which_command(executable: &str) -> Option<String> {
-    if let Ok(path_var) = std::env::var("PATH") {
-        for path_dir in path_var.split(':') {
-            let full_path = format!("{}/{}", path_dir, executable);
-            if std::path::Path::new(&full_path).exists() {
-                return Some(full_path);
-            }
-        }
+    let path_var = std::env::var("PATH").unwrap_or_default();
+    let key = (path_var, executable.to_string());
+
+    let mut cache = WHICH_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(found) = cache.get(&key) {
+        return found.clone();
     }
-    None
+
+    let found = key
+        .0
+        .split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| format!("{}/{}", dir, executable))
+        .find(|full_path| is_executable_file(Path::new(full_path)));
+
+    cache.insert(key, found.clone());
+    found
+}
+
+/// Forget the cached PATH lookups used for TryExec and terminal detection
+pub fn clear_executable_cache() {
+    let mut cache = WHICH_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    *cache = None;
 }
 
 /// Find an available terminal emulator
@@ -588,74 +808,3 @@ fn find_terminal() -> Option<String> {
     
     None
 }
-
-/// Escape a string for safe shell usage
-fn shell_escape(s: &str) -> String {
-    if s.chars().any(|c| " \t\n'\"\\$`()[]{}?*~&|;<>".contains(c)) {
-        format!("'{}'", s.replace('\'', "'\"'\"'"))
-    } else {
-        s.to_string()
-    }
-}
-
-/// Parse a command line into program and arguments, handling quotes
-fn parse_command_line(command: &str) -> Result<(String, Vec<String>), ExecuteError> {
-    let mut parts = Vec::new();
-    let mut current = String::new();
-    let mut in_quotes = false;
-    let mut quote_char = '"';
-    let mut chars = command.chars().peekable();
-    
-    while let Some(ch) = chars.next() {
-        match ch {
-            '"' | '\'' if !in_quotes => {
-                in_quotes = true;
-                quote_char = ch;
-            },
-            ch if ch == quote_char && in_quotes => {
-                in_quotes = false;
-            },
-            '\\' if in_quotes => {
-                // Handle escape sequences in quotes
-                if let Some(&next_ch) = chars.peek() {
-                    chars.next();
-                    match next_ch {
-                        '"' | '\'' | '\\' | '$' | '`' => current.push(next_ch),
-                        _ => {
-                            current.push('\\');
-                            current.push(next_ch);
-                        }
-                    }
-                } else {
-                    current.push('\\');
-                }
-            },
-            ' ' | '\t' if !in_quotes => {
-                if !current.is_empty() {
-                    parts.push(current);
-                    current = String::new();
-                }
-                // Skip multiple spaces
-                while chars.peek() == Some(&' ') || chars.peek() == Some(&'\t') {
-                    chars.next();
-                }
-            },
-            _ => current.push(ch),
-        }
-    }
-    
-    if !current.is_empty() {
-        parts.push(current);
-    }
-    
-    if in_quotes {
-        return Err(ExecuteError::InvalidCommand("Unterminated quote".to_string()));
-    }
-    
-    if parts.is_empty() {
-        return Err(ExecuteError::InvalidCommand("Empty command".to_string()));
-    }
-    
-    let program = parts.remove(0);
-    Ok((program, parts))
-}
@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+use freedesktop_core::info::Info;
+
+use crate::parser::DesktopEntry;
+use crate::{mime_type_for_path, show_in, ApplicationEntry, EntryGroup, ExecuteError, LaunchContext, LaunchedApp, MimeApps, ParseError};
+
+/// A `Type=Link` desktop entry, pointing at a URL
+#[derive(Debug, Clone, Default)]
+pub struct LinkEntry {
+    pub(crate) inner: DesktopEntry,
+}
+
+impl LinkEntry {
+    /// Load a Link .desktop file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let desktop_entry = DesktopEntry::from_path(path)?;
+        Ok(LinkEntry {
+            inner: desktop_entry,
+        })
+    }
+
+    /// Get the name in the current locale
+    pub fn name(&self) -> Option<String> {
+        self.get_localized_string("Name", Info::current_locale().as_deref())
+    }
+
+    /// Get the URL the link points at
+    pub fn url(&self) -> Option<String> {
+        self.get_string("URL")
+    }
+
+    /// Get the icon name or path
+    pub fn icon(&self) -> Option<String> {
+        self.get_string("Icon")
+    }
+
+    /// Get the comment in the current locale
+    pub fn comment(&self) -> Option<String> {
+        self.get_localized_string("Comment", Info::current_locale().as_deref())
+    }
+
+    /// Get a string value from the Desktop Entry group
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.get_localized_string(key, None)
+    }
+
+    /// Get a localized string value from the Desktop Entry group
+    pub fn get_localized_string(&self, key: &str, locale: Option<&str>) -> Option<String> {
+        self.inner
+            .get_desktop_entry_group()
+            .and_then(|group| group.get_localized_field(key, locale))
+            .and_then(|value| value.as_string())
+    }
+
+    /// Get a boolean value from the Desktop Entry group
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.inner
+            .get_desktop_entry_group()
+            .and_then(|group| group.get_field(key))
+            .and_then(|value| value.as_bool())
+    }
+
+    /// Any group of the file by name, e.g. "X-Flatpak"
+    pub fn group(&self, name: &str) -> Option<EntryGroup<'_>> {
+        self.inner.group(name)
+    }
+
+    /// Names of all groups, in file order
+    pub fn groups(&self) -> Vec<&str> {
+        self.inner.group_names()
+    }
+
+    /// Get the file path of this link entry
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Check if the entry should be shown in the current desktop's menus,
+    /// taking Hidden, NoDisplay, OnlyShowIn and NotShowIn into account
+    pub fn should_show(&self) -> bool {
+        !self.is_hidden() && !self.no_display() && self.should_show_in(&Info::current_desktops())
+    }
+
+    /// Evaluate OnlyShowIn and NotShowIn for a list of desktop names
+    pub fn should_show_in<S: AsRef<str>>(&self, desktops: &[S]) -> bool {
+        show_in(
+            &self.get_list("OnlyShowIn"),
+            &self.get_list("NotShowIn"),
+            desktops,
+        )
+    }
+
+    fn get_list(&self, key: &str) -> Option<Vec<String>> {
+        self.inner
+            .get_desktop_entry_group()
+            .and_then(|group| group.get_field(key))
+            .and_then(|value| value.as_list())
+    }
+
+    /// Check if entry should be hidden
+    pub fn is_hidden(&self) -> bool {
+        self.get_bool("Hidden").unwrap_or(false)
+    }
+
+    /// Check if entry should not be displayed in menus
+    pub fn no_display(&self) -> bool {
+        self.get_bool("NoDisplay").unwrap_or(false)
+    }
+
+    /// The URL scheme, e.g. "https". Plain paths count as "file".
+    pub fn scheme(&self) -> Option<String> {
+        let url = self.url()?;
+        Some(url_scheme(&url).unwrap_or("file").to_lowercase())
+    }
+
+    /// The local path a file link points at, e.g. /home/user for
+    /// file:///home/user or a plain /home/user
+    pub fn file_path(&self) -> Option<PathBuf> {
+        let url = self.url()?;
+
+        match url_scheme(&url) {
+            None => Some(PathBuf::from(url)),
+            Some(scheme) if scheme.eq_ignore_ascii_case("file") => {
+                // Skip the authority, file://host/path is a path on that host
+                let rest = &url[scheme.len() + 1..];
+                let path = match rest.strip_prefix("//") {
+                    Some(rest) => &rest[rest.find('/')?..],
+                    None => rest,
+                };
+                Some(PathBuf::from(percent_decode(path)))
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// The application that opens this link: the handler of its URL
+    /// scheme, or for file links the default application for the MIME
+    /// type of the target, falling back to the file manager
+    pub fn handler(&self) -> Option<ApplicationEntry> {
+        let scheme = self.scheme()?;
+        if scheme != "file" {
+            return ApplicationEntry::default_for_scheme(&scheme);
+        }
+
+        self.file_path()
+            .and_then(mime_type_for_path)
+            .and_then(|mime_type| MimeApps::load().default_application(&mime_type))
+            .or_else(ApplicationEntry::default_file_manager)
+    }
+
+    /// Open the URL with its handler, returning the launched processes
+    pub fn open(&self) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        self.open_with_context(&LaunchContext::default())
+    }
+
+    /// Open the URL with its handler, launched as the context says
+    pub fn open_with_context(&self, context: &LaunchContext) -> Result<Vec<LaunchedApp>, ExecuteError> {
+        let url = self
+            .url()
+            .filter(|url| !url.trim().is_empty())
+            .ok_or_else(|| ExecuteError::InvalidCommand("Link has no URL".to_string()))?;
+        let scheme = self.scheme().unwrap_or_default();
+
+        let handler = self
+            .handler()
+            .ok_or_else(|| ExecuteError::NoHandler(format!("x-scheme-handler/{}", scheme)))?;
+
+        // A file link is passed as a path to %f and %F and as is to %u and %U
+        match self.file_path() {
+            Some(path) => handler.execute_with_context(&[&path.to_string_lossy()], &[&url], context),
+            None => handler.execute_with_context(&[], &[&url], context),
+        }
+    }
+}
+
+/// Decode %XX escapes, leaving invalid ones as they are
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The scheme of a URL as defined by RFC 3986: a letter followed
+/// by letters, digits, '+', '-' or '.', then ':'
+fn url_scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    valid.then_some(scheme)
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use crate::parser::ParseOptions;
+use crate::{ApplicationEntry, DesktopFileIndex, Entry, ParseError};
+
+/// Why a loaded application isn't shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// Hidden=true, the entry is deleted and masks its ID
+    Hidden,
+    NoDisplay,
+    /// None of the current desktops is in OnlyShowIn
+    OnlyShowIn,
+    /// A current desktop is in NotShowIn
+    NotShowIn,
+    /// The TryExec program isn't installed
+    TryExec,
+}
+
+impl std::fmt::Display for FilterReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterReason::Hidden => write!(f, "Hidden is set"),
+            FilterReason::NoDisplay => write!(f, "NoDisplay is set"),
+            FilterReason::OnlyShowIn => write!(f, "Not listed in OnlyShowIn for the current desktop"),
+            FilterReason::NotShowIn => write!(f, "Listed in NotShowIn for the current desktop"),
+            FilterReason::TryExec => write!(f, "TryExec program is not installed"),
+        }
+    }
+}
+
+/// Everything found while loading applications, for finding out
+/// why an application doesn't show up
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    /// Applications to show
+    pub entries: Vec<ApplicationEntry>,
+    /// Applications that were loaded but aren't shown, with the first reason found
+    pub filtered: Vec<(ApplicationEntry, FilterReason)>,
+    /// Files that failed to parse and directories that couldn't be read
+    pub failures: Vec<(PathBuf, ParseError)>,
+    /// Files ignored because a more important data dir has the same ID,
+    /// with the file that wins. When that file is Hidden=true they are
+    /// masked, and it's in filtered with FilterReason::Hidden.
+    pub shadowed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl DesktopFileIndex {
+    /// Load every application for a list of desktops, reporting failures and
+    /// filtered entries instead of dropping them. Links are left out.
+    pub fn load_applications<S: AsRef<str>>(&self, desktops: &[S]) -> LoadReport {
+        let mut report = LoadReport {
+            failures: self.errors().to_vec(),
+            ..Default::default()
+        };
+
+        for file in self.files() {
+            for shadowed in file.shadowed() {
+                report.shadowed.push((shadowed.clone(), file.path().to_path_buf()));
+            }
+
+            let app = match Entry::from_path_with_options(file.path(), &ParseOptions::lenient()) {
+                Ok(Entry::Application(app)) => app.with_id(file.id()),
+                Ok(_) => continue,
+                Err(e) => {
+                    report.failures.push((file.path().to_path_buf(), e));
+                    continue;
+                }
+            };
+
+            match filter_reason(&app, desktops) {
+                Some(reason) => report.filtered.push((app, reason)),
+                None => report.entries.push(app),
+            }
+        }
+
+        report
+    }
+}
+
+fn filter_reason<S: AsRef<str>>(app: &ApplicationEntry, desktops: &[S]) -> Option<FilterReason> {
+    if app.is_hidden() {
+        return Some(FilterReason::Hidden);
+    }
+    if app.no_display() {
+        return Some(FilterReason::NoDisplay);
+    }
+    if !app.should_show_in(desktops) {
+        let not_show_in = app.get_vec("NotShowIn").unwrap_or_default();
+        let excluded = desktops
+            .iter()
+            .any(|desktop| not_show_in.iter().any(|d| d == desktop.as_ref()));
+        return Some(if excluded {
+            FilterReason::NotShowIn
+        } else {
+            FilterReason::OnlyShowIn
+        });
+    }
+    if !app.is_available() {
+        return Some(FilterReason::TryExec);
+    }
+
+    None
+}
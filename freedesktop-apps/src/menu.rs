@@ -0,0 +1,770 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::{data_dirs, desktop_files_in, directory, ApplicationEntry, DirectoryEntry, ParseOptions};
+
+mod editor;
+mod layout;
+
+pub use editor::{user_edits_path, MenuEditor};
+pub use layout::{LayoutAttributes, LayoutNode, MenuItem, MergeType};
+use layout::LayoutOptions;
+
+#[derive(Debug, Clone)]
+pub enum MenuError {
+    NotFound(String),
+    IoError(String),
+    InvalidFormat(String),
+}
+
+impl std::fmt::Display for MenuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MenuError::NotFound(msg) => write!(f, "Menu not found: {}", msg),
+            MenuError::IoError(msg) => write!(f, "IO error: {}", msg),
+            MenuError::InvalidFormat(msg) => write!(f, "Invalid format: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MenuError {}
+
+/// $XDG_CONFIG_HOME followed by $XDG_CONFIG_DIRS, most important first
+fn config_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![freedesktop_core::xdg_config_home()];
+    dirs.extend(freedesktop_core::xdg_config_dirs());
+    dirs
+}
+
+/// Locate the root applications menu.
+///
+/// Per the spec this is the first ${XDG_MENU_PREFIX}applications.menu found
+/// in the menus/ directory of $XDG_CONFIG_HOME and $XDG_CONFIG_DIRS. When
+/// the prefixed file doesn't exist we fall back to plain applications.menu,
+/// which is what most desktops ship.
+pub fn menu_file_path() -> Option<PathBuf> {
+    let prefix = std::env::var("XDG_MENU_PREFIX").unwrap_or_default();
+    let mut file_names = vec![format!("{}applications.menu", prefix)];
+    if !prefix.is_empty() {
+        file_names.push("applications.menu".to_string());
+    }
+
+    for file_name in &file_names {
+        for dir in config_dirs() {
+            let path = dir.join("menus").join(file_name);
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+    }
+
+    None
+}
+
+/// A matching rule from an <Include> or <Exclude> element
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Rule {
+    Filename(String),
+    Category(String),
+    All,
+    And(Vec<Rule>),
+    Or(Vec<Rule>),
+    Not(Vec<Rule>),
+}
+
+impl Rule {
+    fn matches(&self, candidate: &Candidate) -> bool {
+        match self {
+            Rule::Filename(id) => candidate.id == *id,
+            Rule::Category(category) => candidate.categories.contains(category),
+            Rule::All => true,
+            Rule::And(rules) => rules.iter().all(|r| r.matches(candidate)),
+            Rule::Or(rules) => rules.iter().any(|r| r.matches(candidate)),
+            Rule::Not(rules) => !rules.iter().any(|r| r.matches(candidate)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RuleOp {
+    Include(Rule),
+    Exclude(Rule),
+}
+
+/// A <Menu> element after merging, before it is evaluated
+/// against the installed desktop entries.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MenuDef {
+    pub name: String,
+    pub app_dirs: Vec<PathBuf>,
+    pub directory_dirs: Vec<PathBuf>,
+    pub directories: Vec<String>,
+    pub only_unallocated: Option<bool>,
+    pub deleted: Option<bool>,
+    pub rules: Vec<RuleOp>,
+    pub moves: Vec<(String, String)>,
+    pub layout: Option<Vec<LayoutNode>>,
+    pub default_layout: Option<DefaultLayout>,
+    pub submenus: Vec<MenuDef>,
+}
+
+/// A <DefaultLayout> element. It applies to the menu it is
+/// declared in and is inherited by all submenus.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DefaultLayout {
+    pub attributes: LayoutAttributes,
+    pub nodes: Vec<LayoutNode>,
+}
+
+impl MenuDef {
+    /// Append the contents of another definition, as if its
+    /// children appeared at the end of this menu. The name
+    /// of the other menu is ignored.
+    fn absorb(&mut self, other: MenuDef) {
+        self.app_dirs.extend(other.app_dirs);
+        self.directory_dirs.extend(other.directory_dirs);
+        self.directories.extend(other.directories);
+        self.rules.extend(other.rules);
+        self.moves.extend(other.moves);
+        self.submenus.extend(other.submenus);
+
+        if other.only_unallocated.is_some() {
+            self.only_unallocated = other.only_unallocated;
+        }
+        if other.deleted.is_some() {
+            self.deleted = other.deleted;
+        }
+        if other.layout.is_some() {
+            self.layout = other.layout;
+        }
+        if other.default_layout.is_some() {
+            self.default_layout = other.default_layout;
+        }
+    }
+
+    /// Merge submenus with the same name into the first one with that
+    /// name, and drop duplicate directories keeping the last occurrence,
+    /// since later elements take precedence.
+    fn fold_duplicates(&mut self) {
+        let mut folded: Vec<MenuDef> = Vec::new();
+        for submenu in std::mem::take(&mut self.submenus) {
+            match folded.iter_mut().find(|m| m.name == submenu.name) {
+                Some(existing) => existing.absorb(submenu),
+                None => folded.push(submenu),
+            }
+        }
+        self.submenus = folded;
+
+        dedup_keep_last(&mut self.app_dirs);
+        dedup_keep_last(&mut self.directory_dirs);
+        dedup_keep_last(&mut self.directories);
+
+        for submenu in &mut self.submenus {
+            submenu.fold_duplicates();
+        }
+    }
+
+    /// Process <Move> elements. Paths are relative to the menu
+    /// containing the <Move>. Moving onto an existing menu merges
+    /// the two.
+    fn apply_moves(&mut self) {
+        for (old, new) in std::mem::take(&mut self.moves) {
+            if let Some(moved) = self.take_submenu(&old) {
+                self.insert_submenu(&new, moved);
+            }
+        }
+
+        self.fold_duplicates();
+
+        for submenu in &mut self.submenus {
+            submenu.apply_moves();
+        }
+    }
+
+    fn take_submenu(&mut self, path: &str) -> Option<MenuDef> {
+        let (first, rest) = split_menu_path(path);
+        let index = self.submenus.iter().position(|m| m.name == first)?;
+
+        match rest {
+            Some(rest) => self.submenus[index].take_submenu(rest),
+            None => Some(self.submenus.remove(index)),
+        }
+    }
+
+    fn insert_submenu(&mut self, path: &str, menu: MenuDef) {
+        let (first, rest) = split_menu_path(path);
+
+        let index = match self.submenus.iter().position(|m| m.name == first) {
+            Some(index) => index,
+            None => {
+                self.submenus.push(MenuDef {
+                    name: first.to_string(),
+                    ..Default::default()
+                });
+                self.submenus.len() - 1
+            }
+        };
+
+        match rest {
+            Some(rest) => self.submenus[index].insert_submenu(rest, menu),
+            None => self.submenus[index].absorb(menu),
+        }
+    }
+}
+
+fn split_menu_path(path: &str) -> (&str, Option<&str>) {
+    let path = path.trim_matches('/');
+    match path.split_once('/') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (path, None),
+    }
+}
+
+fn dedup_keep_last<T: PartialEq>(items: &mut Vec<T>) {
+    let mut kept: Vec<T> = Vec::new();
+    for item in std::mem::take(items).into_iter().rev() {
+        if !kept.contains(&item) {
+            kept.push(item);
+        }
+    }
+    kept.reverse();
+    *items = kept;
+}
+
+/// Reads menu files into a MenuDef, resolving all merge elements
+struct Loader {
+    /// Name of the merge directory used for <DefaultMergeDirs>
+    merged_dir: String,
+    /// Files currently being loaded, to break merge loops
+    stack: Vec<PathBuf>,
+}
+
+impl Loader {
+    fn load(path: &Path) -> Result<MenuDef, MenuError> {
+        let mut loader = Loader {
+            merged_dir: merged_dir_name(path),
+            stack: Vec::new(),
+        };
+
+        let mut def = MenuDef::default();
+        loader.load_file(path, &mut def)?;
+        Ok(def)
+    }
+
+    fn load_file(&mut self, path: &Path, def: &mut MenuDef) -> Result<(), MenuError> {
+        let canonical = path.canonicalize().map_err(|e| {
+            MenuError::IoError(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+
+        if self.stack.contains(&canonical) {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(&canonical).map_err(|e| {
+            MenuError::IoError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document = Document::parse_with_options(&content, options)
+            .map_err(|e| MenuError::InvalidFormat(format!("{}: {}", path.display(), e)))?;
+
+        let root = document.root_element();
+        if !root.has_tag_name("Menu") {
+            return Err(MenuError::InvalidFormat(format!(
+                "{}: root element must be <Menu>",
+                path.display()
+            )));
+        }
+
+        self.stack.push(canonical.clone());
+        let result = self.read_children(root, &canonical, def);
+        self.stack.pop();
+
+        result
+    }
+
+    fn read_menu(&mut self, node: Node, file: &Path) -> Result<MenuDef, MenuError> {
+        let mut def = MenuDef::default();
+        self.read_children(node, file, &mut def)?;
+        Ok(def)
+    }
+
+    fn read_children(&mut self, node: Node, file: &Path, def: &mut MenuDef) -> Result<(), MenuError> {
+        let base_dir = file.parent().unwrap_or(Path::new("/"));
+
+        for child in node.children().filter(|n| n.is_element()) {
+            match child.tag_name().name() {
+                "Name" => def.name = node_text(child),
+                "AppDir" => def.app_dirs.push(resolve_path(base_dir, &node_text(child))),
+                "DefaultAppDirs" => {
+                    // Reverse order so the most important dir comes last and wins
+                    def.app_dirs
+                        .extend(data_dirs().iter().rev().map(|d| d.join("applications")));
+                }
+                "DirectoryDir" => def
+                    .directory_dirs
+                    .push(resolve_path(base_dir, &node_text(child))),
+                "DefaultDirectoryDirs" => {
+                    def.directory_dirs.extend(
+                        data_dirs()
+                            .iter()
+                            .rev()
+                            .map(|d| d.join("desktop-directories")),
+                    );
+                }
+                "Directory" => def.directories.push(node_text(child)),
+                "OnlyUnallocated" => def.only_unallocated = Some(true),
+                "NotOnlyUnallocated" => def.only_unallocated = Some(false),
+                "Deleted" => def.deleted = Some(true),
+                "NotDeleted" => def.deleted = Some(false),
+                "Include" => def.rules.push(RuleOp::Include(Rule::Or(read_rules(child)))),
+                "Exclude" => def.rules.push(RuleOp::Exclude(Rule::Or(read_rules(child)))),
+                "Menu" => {
+                    let submenu = self.read_menu(child, file)?;
+                    def.submenus.push(submenu);
+                }
+                "Move" => read_moves(child, &mut def.moves),
+                "MergeFile" => {
+                    let text = node_text(child);
+                    match child.attribute("type") {
+                        Some("parent") => self.merge_parent(file, def),
+                        _ => self.merge_file(&resolve_path(base_dir, &text), def),
+                    }
+                }
+                "MergeDir" => self.merge_dir(&resolve_path(base_dir, &node_text(child)), def),
+                "DefaultMergeDirs" => {
+                    let merged_dir = self.merged_dir.clone();
+                    for dir in config_dirs().iter().rev() {
+                        self.merge_dir(&dir.join("menus").join(&merged_dir), def);
+                    }
+                }
+                "Layout" => def.layout = Some(LayoutNode::read_all(child)),
+                "DefaultLayout" => {
+                    def.default_layout = Some(DefaultLayout {
+                        attributes: LayoutAttributes::from_node(child),
+                        nodes: LayoutNode::read_all(child),
+                    });
+                }
+                // <LegacyDir> and <KDELegacyDirs> are deprecated and
+                // everything else is unknown. Both are ignored.
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merge the root <Menu> of another file into `def`. Per the spec,
+    /// merge files that don't exist or fail to parse are ignored.
+    fn merge_file(&mut self, path: &Path, def: &mut MenuDef) {
+        let mut merged = MenuDef::default();
+        if self.load_file(path, &mut merged).is_ok() {
+            def.absorb(merged);
+        }
+    }
+
+    /// Merge every .menu file in a directory, in a stable order
+    fn merge_dir(&mut self, dir: &Path, def: &mut MenuDef) {
+        let Ok(dir_entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        let mut files: Vec<PathBuf> = dir_entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "menu"))
+            .collect();
+        files.sort();
+
+        for file in files {
+            self.merge_file(&file, def);
+        }
+    }
+
+    /// <MergeFile type="parent"> merges the file with the same path
+    /// relative to menus/ in the next, less important, config dir.
+    fn merge_parent(&mut self, file: &Path, def: &mut MenuDef) {
+        let dirs = config_dirs();
+
+        let position = dirs.iter().enumerate().find_map(|(i, dir)| {
+            let menus_dir = dir.join("menus");
+            let menus_dir = menus_dir.canonicalize().unwrap_or(menus_dir);
+            file.strip_prefix(&menus_dir)
+                .ok()
+                .map(|relative| (i, relative.to_path_buf()))
+        });
+
+        let Some((index, relative)) = position else {
+            return;
+        };
+
+        for dir in &dirs[index + 1..] {
+            let candidate = dir.join("menus").join(&relative);
+            if candidate.is_file() {
+                self.merge_file(&candidate, def);
+                return;
+            }
+        }
+    }
+}
+
+/// The directory used for <DefaultMergeDirs>. Per the spec every
+/// applications menu uses applications-merged, no matter the
+/// ${XDG_MENU_PREFIX}. Other menus use <basename>-merged.
+fn merged_dir_name(menu_file: &Path) -> String {
+    let stem = menu_file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if stem.is_empty() || stem.ends_with("applications") {
+        "applications-merged".to_string()
+    } else {
+        format!("{}-merged", stem)
+    }
+}
+
+fn node_text(node: Node) -> String {
+    node.text().map(|t| t.trim().to_string()).unwrap_or_default()
+}
+
+fn resolve_path(base_dir: &Path, path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    }
+}
+
+fn read_rules(node: Node) -> Vec<Rule> {
+    node.children()
+        .filter(|n| n.is_element())
+        .filter_map(|child| match child.tag_name().name() {
+            "Filename" => Some(Rule::Filename(node_text(child))),
+            "Category" => Some(Rule::Category(node_text(child))),
+            "All" => Some(Rule::All),
+            "And" => Some(Rule::And(read_rules(child))),
+            "Or" => Some(Rule::Or(read_rules(child))),
+            "Not" => Some(Rule::Not(read_rules(child))),
+            _ => None,
+        })
+        .collect()
+}
+
+fn read_moves(node: Node, moves: &mut Vec<(String, String)>) {
+    let mut old: Option<String> = None;
+
+    for child in node.children().filter(|n| n.is_element()) {
+        match child.tag_name().name() {
+            "Old" => old = Some(node_text(child)),
+            "New" => {
+                if let Some(old) = old.take() {
+                    moves.push((old, node_text(child)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A desktop entry available to a menu, with its ID relative to the AppDir
+#[derive(Debug)]
+struct Candidate {
+    id: String,
+    categories: Vec<String>,
+    entry: ApplicationEntry,
+}
+
+/// Evaluates a MenuDef against the desktop entries in its AppDirs
+#[derive(Default)]
+struct Evaluator {
+    dir_cache: BTreeMap<PathBuf, Vec<Rc<Candidate>>>,
+    allocated: HashSet<String>,
+}
+
+impl Evaluator {
+    fn candidates_in(&mut self, dir: &Path) -> Vec<Rc<Candidate>> {
+        self.dir_cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                desktop_files_in(dir)
+                    .into_iter()
+                    .filter_map(|(id, path)| {
+                        let entry = ApplicationEntry::from_path_with_options(&path, &ParseOptions::lenient()).ok()?;
+                        Some(Rc::new(Candidate {
+                            id,
+                            categories: entry.categories().unwrap_or_default(),
+                            entry,
+                        }))
+                    })
+                    .collect()
+            })
+            .clone()
+    }
+
+    /// All entries from the given AppDirs. Later dirs take precedence
+    /// when two of them contain the same desktop file ID.
+    fn pool(&mut self, app_dirs: &[PathBuf]) -> BTreeMap<String, Rc<Candidate>> {
+        let mut pool = BTreeMap::new();
+        for dir in app_dirs {
+            for candidate in self.candidates_in(dir) {
+                pool.insert(candidate.id.clone(), candidate);
+            }
+        }
+        pool
+    }
+
+    /// Apply <Include> and <Exclude> in document order
+    fn matching(&mut self, def: &MenuDef, app_dirs: &[PathBuf]) -> BTreeMap<String, Rc<Candidate>> {
+        let pool = self.pool(app_dirs);
+        let mut included: BTreeMap<String, Rc<Candidate>> = BTreeMap::new();
+
+        for op in &def.rules {
+            match op {
+                RuleOp::Include(rule) => {
+                    for (id, candidate) in &pool {
+                        // Hidden entries are treated as deleted
+                        if !candidate.entry.is_hidden() && rule.matches(candidate) {
+                            included.insert(id.clone(), candidate.clone());
+                        }
+                    }
+                }
+                RuleOp::Exclude(rule) => {
+                    included.retain(|_, candidate| !rule.matches(candidate));
+                }
+            }
+        }
+
+        included
+    }
+
+    /// First pass: record every entry allocated by a menu
+    /// that doesn't have <OnlyUnallocated>. Deleted menus
+    /// don't allocate anything.
+    fn allocate(&mut self, def: &MenuDef, parent_app_dirs: &[PathBuf]) {
+        if def.deleted.unwrap_or(false) {
+            return;
+        }
+
+        let app_dirs = inherit(parent_app_dirs, &def.app_dirs);
+
+        if !def.only_unallocated.unwrap_or(false) {
+            let ids: Vec<String> = self.matching(def, &app_dirs).into_keys().collect();
+            self.allocated.extend(ids);
+        }
+
+        for submenu in &def.submenus {
+            self.allocate(submenu, &app_dirs);
+        }
+    }
+
+    /// Second pass: build the final menu tree. `options` are the layout
+    /// options of this menu as seen by its parent.
+    fn build(&mut self, def: &MenuDef, parent: &Inherited, options: LayoutOptions) -> Menu {
+        let app_dirs = inherit(&parent.app_dirs, &def.app_dirs);
+        let directory_dirs = inherit(&parent.directory_dirs, &def.directory_dirs);
+        let default_layout = def
+            .default_layout
+            .clone()
+            .or_else(|| parent.default_layout.clone())
+            .unwrap_or_default();
+
+        let layout = match &def.layout {
+            Some(layout) => layout.clone(),
+            None if !default_layout.nodes.is_empty() => default_layout.nodes.clone(),
+            None => LayoutNode::default_layout(),
+        };
+
+        let inherited = Inherited {
+            app_dirs: app_dirs.clone(),
+            directory_dirs: directory_dirs.clone(),
+            default_layout: Some(default_layout.clone()),
+        };
+
+        let mut included = self.matching(def, &app_dirs);
+        if def.only_unallocated.unwrap_or(false) {
+            included.retain(|id, _| !self.allocated.contains(id));
+        }
+
+        let mut items: Vec<(String, ApplicationEntry)> = included
+            .into_iter()
+            .filter(|(_, candidate)| candidate.entry.should_show())
+            .map(|(id, candidate)| (id, candidate.entry.clone()))
+            .collect();
+        items.sort_by_key(|(_, entry)| entry.name().unwrap_or_default().to_lowercase());
+
+        let mut submenus: Vec<Menu> = Vec::new();
+        for submenu in def.submenus.iter().filter(|m| !m.deleted.unwrap_or(false)) {
+            // Attributes on <Menuname> override the <DefaultLayout> in effect
+            let defaults = submenu
+                .default_layout
+                .as_ref()
+                .map(|d| &d.attributes)
+                .unwrap_or(&default_layout.attributes);
+            let submenu_options = layout
+                .iter()
+                .find_map(|node| match node {
+                    LayoutNode::Menuname(name, attributes) if *name == submenu.name => {
+                        Some(attributes.over(defaults))
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| defaults.clone())
+                .resolve();
+
+            let built = self.build(submenu, &inherited, submenu_options);
+            if built.directory.as_ref().is_some_and(|d| d.no_display()) {
+                continue;
+            }
+            if !built.is_empty() || submenu_options.show_empty {
+                submenus.push(built);
+            }
+        }
+        submenus.sort_by_key(|submenu| submenu.display_name().to_lowercase());
+
+        let (entry_ids, entries) = items.into_iter().unzip();
+
+        Menu {
+            name: def.name.clone(),
+            directory: find_directory(&def.directories, &directory_dirs),
+            entry_ids,
+            entries,
+            submenus,
+            layout,
+            options,
+        }
+    }
+}
+
+/// What a submenu inherits from its parent
+#[derive(Debug, Default)]
+struct Inherited {
+    app_dirs: Vec<PathBuf>,
+    directory_dirs: Vec<PathBuf>,
+    default_layout: Option<DefaultLayout>,
+}
+
+/// Submenus see the dirs of their parents, with their own taking priority
+fn inherit(parent: &[PathBuf], own: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs = parent.to_vec();
+    dirs.extend(own.iter().cloned());
+    dirs
+}
+
+/// The last <Directory> that can be loaded wins, looked up
+/// in the DirectoryDirs with the last one taking priority
+fn find_directory(directories: &[String], directory_dirs: &[PathBuf]) -> Option<DirectoryEntry> {
+    directories.iter().rev().find_map(|id| {
+        directory_dirs
+            .iter()
+            .rev()
+            .filter_map(|dir| directory::find_in(dir, id))
+            .find_map(|path| DirectoryEntry::from_path_with_options(path, &ParseOptions::lenient()).ok())
+    })
+}
+
+/// An evaluated menu following the Desktop Menu Specification
+#[derive(Debug, Clone, Default)]
+pub struct Menu {
+    name: String,
+    directory: Option<DirectoryEntry>,
+    entry_ids: Vec<String>,
+    entries: Vec<ApplicationEntry>,
+    submenus: Vec<Menu>,
+    layout: Vec<LayoutNode>,
+    options: LayoutOptions,
+}
+
+impl Menu {
+    /// Load and evaluate the applications menu of the current desktop
+    pub fn load() -> Result<Menu, MenuError> {
+        let path = menu_file_path()
+            .ok_or_else(|| MenuError::NotFound("No applications.menu found".to_string()))?;
+
+        Self::from_path(path)
+    }
+
+    /// Load and evaluate a menu file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Menu, MenuError> {
+        let mut def = Loader::load(path.as_ref())?;
+        def.fold_duplicates();
+        def.apply_moves();
+
+        let mut evaluator = Evaluator::default();
+        evaluator.allocate(&def, &[]);
+        Ok(evaluator.build(&def, &Inherited::default(), LayoutOptions::default()))
+    }
+
+    /// The <Name> of this menu
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name to show for this menu: the Name of its
+    /// .directory file, or the <Name> if it has none
+    pub fn display_name(&self) -> String {
+        self.directory
+            .as_ref()
+            .and_then(|d| d.name())
+            .unwrap_or_else(|| self.name.clone())
+    }
+
+    /// The icon of this menu, from its .directory file
+    pub fn icon(&self) -> Option<String> {
+        self.directory.as_ref().and_then(|d| d.icon())
+    }
+
+    /// The .directory file describing this menu, if any
+    pub fn directory(&self) -> Option<&DirectoryEntry> {
+        self.directory.as_ref()
+    }
+
+    /// Path of the .directory file describing this menu, if any
+    pub fn directory_path(&self) -> Option<&Path> {
+        self.directory.as_ref().map(|d| d.path())
+    }
+
+    /// Applications in this menu, sorted by name
+    pub fn entries(&self) -> &[ApplicationEntry] {
+        &self.entries
+    }
+
+    /// Desktop file IDs of the applications in this menu, in the same
+    /// order as entries(). IDs are relative to the AppDir the entry
+    /// was found in, e.g. "org.gnome.Calculator.desktop".
+    pub fn entry_ids(&self) -> &[String] {
+        &self.entry_ids
+    }
+
+    /// Child menus, sorted by display name. Empty menus are only
+    /// included when their layout sets show_empty="true", menus
+    /// whose .directory file sets NoDisplay=true never are.
+    pub fn submenus(&self) -> &[Menu] {
+        &self.submenus
+    }
+
+    /// Find a descendant menu by a '/' separated path, e.g. "Games/Arcade"
+    pub fn submenu(&self, path: &str) -> Option<&Menu> {
+        let (first, rest) = split_menu_path(path);
+        let submenu = self.submenus.iter().find(|m| m.name == first)?;
+
+        match rest {
+            Some(rest) => submenu.submenu(rest),
+            None => Some(submenu),
+        }
+    }
+
+    /// A menu is empty when it has no applications and no non-empty submenus
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.submenus.iter().all(|m| m.is_empty())
+    }
+}
@@ -0,0 +1,372 @@
+use std::path::{Path, PathBuf};
+
+use super::{menu_file_path, split_menu_path, Loader, MenuDef, MenuError, Rule, RuleOp};
+use super::layout::{LayoutNode, MergeType};
+
+/// File the editor stores its changes in, inside applications-merged
+const EDITS_FILE: &str = "freedesktop-user-edits.menu";
+
+const DOCTYPE: &str = "<!DOCTYPE Menu PUBLIC \"-//freedesktop//DTD Menu 1.0//EN\"\n \"http://www.freedesktop.org/standards/menu-spec/1.0/menu.dtd\">";
+
+/// Default location of the user's menu edits:
+/// $XDG_CONFIG_HOME/menus/applications-merged/freedesktop-user-edits.menu
+pub fn user_edits_path() -> PathBuf {
+    freedesktop_core::xdg_config_home()
+        .join("menus")
+        .join("applications-merged")
+        .join(EDITS_FILE)
+}
+
+/// Edits the user's menu the way menu editors like alacarte do.
+///
+/// Changes are stored as a menu file in $XDG_CONFIG_HOME/menus/applications-merged,
+/// which every desktop merges into its applications menu through <DefaultMergeDirs>,
+/// so they apply no matter which desktop is running. If the active root menu doesn't
+/// use <DefaultMergeDirs>, saving also writes a $XDG_CONFIG_HOME/menus/applications.menu
+/// that merges the system menu followed by the edits.
+///
+/// Menu paths are relative to the root menu, e.g. "Games/Arcade".
+/// An empty path refers to the root menu itself.
+#[derive(Debug, Clone)]
+pub struct MenuEditor {
+    path: PathBuf,
+    edits: MenuDef,
+}
+
+impl MenuEditor {
+    /// Load the edits from the default location
+    pub fn load() -> Result<Self, MenuError> {
+        Self::from_path(user_edits_path())
+    }
+
+    /// Load the edits from a specific file. A missing file means no edits yet.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, MenuError> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut edits = if path.exists() {
+            Loader::load(&path)?
+        } else {
+            MenuDef::default()
+        };
+
+        if edits.name.is_empty() {
+            edits.name = "Applications".to_string();
+        }
+
+        Ok(Self { path, edits })
+    }
+
+    /// Get the file path the edits are saved to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Hide an application from a menu
+    pub fn hide_entry(&mut self, menu_path: &str, id: &str) {
+        let menu = self.menu_mut(menu_path);
+        remove_filename_rules(menu, id);
+        menu.rules
+            .push(RuleOp::Exclude(Rule::Or(vec![Rule::Filename(desktop_id(id))])));
+    }
+
+    /// Show an application in a menu, even if its categories don't match
+    pub fn show_entry(&mut self, menu_path: &str, id: &str) {
+        let menu = self.menu_mut(menu_path);
+        remove_filename_rules(menu, id);
+        menu.rules
+            .push(RuleOp::Include(Rule::Or(vec![Rule::Filename(desktop_id(id))])));
+    }
+
+    /// Move an application from one menu to another
+    pub fn move_entry(&mut self, id: &str, from: &str, to: &str) {
+        self.hide_entry(from, id);
+        self.show_entry(to, id);
+    }
+
+    /// Hide a whole menu
+    pub fn hide_menu(&mut self, menu_path: &str) {
+        self.menu_mut(menu_path).deleted = Some(true);
+    }
+
+    /// Show a menu that was hidden, by the user or the system
+    pub fn show_menu(&mut self, menu_path: &str) {
+        self.menu_mut(menu_path).deleted = Some(false);
+    }
+
+    /// Move a menu, merging it with the menu at the new path if there is one
+    pub fn move_menu(&mut self, old_path: &str, new_path: &str) {
+        self.edits
+            .moves
+            .push((old_path.to_string(), new_path.to_string()));
+    }
+
+    /// Set the order of the items in a menu
+    pub fn set_layout(&mut self, menu_path: &str, layout: &[LayoutNode]) {
+        self.menu_mut(menu_path).layout = Some(layout.to_vec());
+    }
+
+    /// Go back to the layout of the system menu
+    pub fn reset_layout(&mut self, menu_path: &str) {
+        self.menu_mut(menu_path).layout = None;
+    }
+
+    /// Write the edits to disk
+    pub fn save(&self) -> Result<(), MenuError> {
+        write_file(&self.path, &self.to_xml())?;
+
+        if self.path == user_edits_path() {
+            ensure_root_merges_edits()?;
+        }
+
+        Ok(())
+    }
+
+    /// Restore the system menu by deleting all edits
+    pub fn reset(&mut self) -> Result<(), MenuError> {
+        self.edits = MenuDef {
+            name: self.edits.name.clone(),
+            ..Default::default()
+        };
+
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)
+                .map_err(|e| MenuError::IoError(format!("Failed to remove {}: {}", self.path.display(), e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// The edits as a menu file
+    pub fn to_xml(&self) -> String {
+        let mut xml = format!("{}\n", DOCTYPE);
+        write_menu(&self.edits, 0, &mut xml);
+        xml
+    }
+
+    fn menu_mut(&mut self, menu_path: &str) -> &mut MenuDef {
+        let mut menu = &mut self.edits;
+        let mut rest = Some(menu_path);
+
+        while let Some(path) = rest.filter(|p| !p.trim_matches('/').is_empty()) {
+            let (name, next) = split_menu_path(path);
+            rest = next;
+
+            let index = match menu.submenus.iter().position(|m| m.name == name) {
+                Some(index) => index,
+                None => {
+                    menu.submenus.push(MenuDef {
+                        name: name.to_string(),
+                        ..Default::default()
+                    });
+                    menu.submenus.len() - 1
+                }
+            };
+            menu = &mut menu.submenus[index];
+        }
+
+        menu
+    }
+}
+
+/// Menu files refer to entries by their full desktop file ID
+fn desktop_id(id: &str) -> String {
+    if id.ends_with(".desktop") {
+        id.to_string()
+    } else {
+        format!("{}.desktop", id)
+    }
+}
+
+/// Drop earlier show/hide edits for an entry so only the latest one is kept
+fn remove_filename_rules(menu: &mut MenuDef, id: &str) {
+    let filename = Rule::Or(vec![Rule::Filename(desktop_id(id))]);
+    menu.rules.retain(|op| match op {
+        RuleOp::Include(rule) | RuleOp::Exclude(rule) => *rule != filename,
+    });
+}
+
+/// Make sure the edits are merged even when the active root menu doesn't use
+/// <DefaultMergeDirs>, by giving the user a root menu that merges the system one.
+fn ensure_root_merges_edits() -> Result<(), MenuError> {
+    let Some(root) = menu_file_path() else {
+        return Ok(());
+    };
+    let Some(file_name) = root.file_name() else {
+        return Ok(());
+    };
+
+    let user_root = freedesktop_core::xdg_config_home().join("menus").join(file_name);
+    if root == user_root {
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(&root)
+        .map_err(|e| MenuError::IoError(format!("Failed to read {}: {}", root.display(), e)))?;
+    if content.contains("<DefaultMergeDirs") {
+        return Ok(());
+    }
+
+    let xml = format!(
+        "{}\n<Menu>\n  <Name>Applications</Name>\n  <MergeFile type=\"parent\">{}</MergeFile>\n  <MergeFile>applications-merged/{}</MergeFile>\n</Menu>\n",
+        DOCTYPE,
+        escape(&file_name.to_string_lossy()),
+        EDITS_FILE
+    );
+
+    write_file(&user_root, &xml)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), MenuError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| MenuError::IoError(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+
+    std::fs::write(path, content)
+        .map_err(|e| MenuError::IoError(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn write_menu(def: &MenuDef, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let inner = "  ".repeat(depth + 1);
+
+    out.push_str(&format!("{}<Menu>\n", indent));
+    out.push_str(&format!("{}<Name>{}</Name>\n", inner, escape(&def.name)));
+
+    for dir in &def.app_dirs {
+        out.push_str(&format!("{}<AppDir>{}</AppDir>\n", inner, escape(&dir.to_string_lossy())));
+    }
+    for dir in &def.directory_dirs {
+        out.push_str(&format!("{}<DirectoryDir>{}</DirectoryDir>\n", inner, escape(&dir.to_string_lossy())));
+    }
+    for directory in &def.directories {
+        out.push_str(&format!("{}<Directory>{}</Directory>\n", inner, escape(directory)));
+    }
+
+    match def.only_unallocated {
+        Some(true) => out.push_str(&format!("{}<OnlyUnallocated/>\n", inner)),
+        Some(false) => out.push_str(&format!("{}<NotOnlyUnallocated/>\n", inner)),
+        None => {}
+    }
+    match def.deleted {
+        Some(true) => out.push_str(&format!("{}<Deleted/>\n", inner)),
+        Some(false) => out.push_str(&format!("{}<NotDeleted/>\n", inner)),
+        None => {}
+    }
+
+    for op in &def.rules {
+        let (tag, rule) = match op {
+            RuleOp::Include(rule) => ("Include", rule),
+            RuleOp::Exclude(rule) => ("Exclude", rule),
+        };
+
+        out.push_str(&format!("{}<{}>\n", inner, tag));
+        // Include and Exclude hold an implicit <Or>
+        match rule {
+            Rule::Or(rules) => {
+                for rule in rules {
+                    write_rule(rule, depth + 2, out);
+                }
+            }
+            rule => write_rule(rule, depth + 2, out),
+        }
+        out.push_str(&format!("{}</{}>\n", inner, tag));
+    }
+
+    for (old, new) in &def.moves {
+        out.push_str(&format!(
+            "{}<Move>\n{}  <Old>{}</Old>\n{}  <New>{}</New>\n{}</Move>\n",
+            inner,
+            inner,
+            escape(old),
+            inner,
+            escape(new),
+            inner
+        ));
+    }
+
+    if let Some(default_layout) = &def.default_layout {
+        out.push_str(&format!(
+            "{}<DefaultLayout{}>\n",
+            inner,
+            default_layout.attributes.to_xml_attributes()
+        ));
+        write_layout_nodes(&default_layout.nodes, depth + 2, out);
+        out.push_str(&format!("{}</DefaultLayout>\n", inner));
+    }
+
+    if let Some(layout) = &def.layout {
+        out.push_str(&format!("{}<Layout>\n", inner));
+        write_layout_nodes(layout, depth + 2, out);
+        out.push_str(&format!("{}</Layout>\n", inner));
+    }
+
+    for submenu in &def.submenus {
+        write_menu(submenu, depth + 1, out);
+    }
+
+    out.push_str(&format!("{}</Menu>\n", indent));
+}
+
+fn write_rule(rule: &Rule, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    let (tag, children) = match rule {
+        Rule::Filename(id) => {
+            out.push_str(&format!("{}<Filename>{}</Filename>\n", indent, escape(id)));
+            return;
+        }
+        Rule::Category(category) => {
+            out.push_str(&format!("{}<Category>{}</Category>\n", indent, escape(category)));
+            return;
+        }
+        Rule::All => {
+            out.push_str(&format!("{}<All/>\n", indent));
+            return;
+        }
+        Rule::And(children) => ("And", children),
+        Rule::Or(children) => ("Or", children),
+        Rule::Not(children) => ("Not", children),
+    };
+
+    out.push_str(&format!("{}<{}>\n", indent, tag));
+    for child in children {
+        write_rule(child, depth + 1, out);
+    }
+    out.push_str(&format!("{}</{}>\n", indent, tag));
+}
+
+fn write_layout_nodes(nodes: &[LayoutNode], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    for node in nodes {
+        match node {
+            LayoutNode::Filename(id) => {
+                out.push_str(&format!("{}<Filename>{}</Filename>\n", indent, escape(id)));
+            }
+            LayoutNode::Menuname(name, attributes) => out.push_str(&format!(
+                "{}<Menuname{}>{}</Menuname>\n",
+                indent,
+                attributes.to_xml_attributes(),
+                escape(name)
+            )),
+            LayoutNode::Separator => out.push_str(&format!("{}<Separator/>\n", indent)),
+            LayoutNode::Merge(merge_type) => {
+                let merge_type = match merge_type {
+                    MergeType::Menus => "menus",
+                    MergeType::Files => "files",
+                    MergeType::All => "all",
+                };
+                out.push_str(&format!("{}<Merge type=\"{}\"/>\n", indent, merge_type));
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,305 @@
+use std::collections::HashSet;
+
+use roxmltree::Node;
+
+use super::{node_text, Menu};
+use crate::ApplicationEntry;
+
+/// Which items a <Merge> element places
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeType {
+    Menus,
+    Files,
+    All,
+}
+
+/// Attributes of <DefaultLayout> and <Menuname>.
+/// Unset values are taken from the <DefaultLayout> in effect.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutAttributes {
+    pub show_empty: Option<bool>,
+    pub inline: Option<bool>,
+    pub inline_limit: Option<usize>,
+    pub inline_header: Option<bool>,
+    pub inline_alias: Option<bool>,
+}
+
+impl LayoutAttributes {
+    /// Values set here take precedence over the ones in `base`
+    pub(crate) fn over(&self, base: &LayoutAttributes) -> LayoutAttributes {
+        LayoutAttributes {
+            show_empty: self.show_empty.or(base.show_empty),
+            inline: self.inline.or(base.inline),
+            inline_limit: self.inline_limit.or(base.inline_limit),
+            inline_header: self.inline_header.or(base.inline_header),
+            inline_alias: self.inline_alias.or(base.inline_alias),
+        }
+    }
+
+    /// Fill in the defaults from the spec
+    pub(crate) fn resolve(&self) -> LayoutOptions {
+        LayoutOptions {
+            show_empty: self.show_empty.unwrap_or(false),
+            inline: self.inline.unwrap_or(false),
+            inline_limit: self.inline_limit.unwrap_or(4),
+            inline_header: self.inline_header.unwrap_or(true),
+            inline_alias: self.inline_alias.unwrap_or(false),
+        }
+    }
+
+    pub(crate) fn from_node(node: Node) -> Self {
+        let bool_attribute = |name: &str| node.attribute(name).map(|v| v.trim() == "true");
+
+        LayoutAttributes {
+            show_empty: bool_attribute("show_empty"),
+            inline: bool_attribute("inline"),
+            inline_limit: node.attribute("inline_limit").and_then(|v| v.trim().parse().ok()),
+            inline_header: bool_attribute("inline_header"),
+            inline_alias: bool_attribute("inline_alias"),
+        }
+    }
+
+    /// The attributes as they appear in XML, e.g. ` inline="true"`
+    pub(crate) fn to_xml_attributes(&self) -> String {
+        let mut attributes = String::new();
+
+        let bools = [
+            ("show_empty", self.show_empty),
+            ("inline", self.inline),
+            ("inline_header", self.inline_header),
+            ("inline_alias", self.inline_alias),
+        ];
+        for (name, value) in bools {
+            if let Some(value) = value {
+                attributes.push_str(&format!(" {}=\"{}\"", name, value));
+            }
+        }
+
+        if let Some(limit) = self.inline_limit {
+            attributes.push_str(&format!(" inline_limit=\"{}\"", limit));
+        }
+
+        attributes
+    }
+}
+
+/// Layout attributes with the defaults filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LayoutOptions {
+    pub show_empty: bool,
+    pub inline: bool,
+    pub inline_limit: usize,
+    pub inline_header: bool,
+    pub inline_alias: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        LayoutAttributes::default().resolve()
+    }
+}
+
+/// A child of <Layout> or <DefaultLayout>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutNode {
+    Filename(String),
+    Menuname(String, LayoutAttributes),
+    Separator,
+    Merge(MergeType),
+}
+
+impl LayoutNode {
+    /// The layout used when no <Layout> or <DefaultLayout> is in effect
+    pub(crate) fn default_layout() -> Vec<LayoutNode> {
+        vec![
+            LayoutNode::Merge(MergeType::Menus),
+            LayoutNode::Merge(MergeType::Files),
+        ]
+    }
+
+    pub(crate) fn read_all(node: Node) -> Vec<LayoutNode> {
+        node.children()
+            .filter(|n| n.is_element())
+            .filter_map(|child| match child.tag_name().name() {
+                "Filename" => Some(LayoutNode::Filename(node_text(child))),
+                "Menuname" => Some(LayoutNode::Menuname(
+                    node_text(child),
+                    LayoutAttributes::from_node(child),
+                )),
+                "Separator" => Some(LayoutNode::Separator),
+                "Merge" => match child.attribute("type") {
+                    Some("menus") => Some(LayoutNode::Merge(MergeType::Menus)),
+                    Some("files") => Some(LayoutNode::Merge(MergeType::Files)),
+                    Some("all") => Some(LayoutNode::Merge(MergeType::All)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// An item of a menu in display order, as produced by Menu::layout()
+#[derive(Debug, Clone, Copy)]
+pub enum MenuItem<'a> {
+    /// An application. `alias` is set when the entry is the only item
+    /// of an inlined submenu with inline_alias="true", in which case
+    /// it should be shown with the name of that submenu.
+    Entry {
+        id: &'a str,
+        entry: &'a ApplicationEntry,
+        alias: Option<&'a Menu>,
+    },
+    Submenu(&'a Menu),
+    /// Header placed before the items of an inlined submenu
+    Header(&'a Menu),
+    Separator,
+}
+
+impl Menu {
+    /// The items of this menu in the order the desktop displays them,
+    /// following the <Layout> or <DefaultLayout> in effect. Submenus
+    /// marked inline have their items placed directly in this list.
+    pub fn layout(&self) -> Vec<MenuItem<'_>> {
+        // Items mentioned explicitly are never placed by a <Merge>
+        let mut explicit_files: HashSet<&str> = HashSet::new();
+        let mut explicit_menus: HashSet<&str> = HashSet::new();
+        for node in &self.layout {
+            match node {
+                LayoutNode::Filename(id) => {
+                    explicit_files.insert(id);
+                }
+                LayoutNode::Menuname(name, _) => {
+                    explicit_menus.insert(name);
+                }
+                _ => {}
+            }
+        }
+
+        let mut placed_files: HashSet<usize> = HashSet::new();
+        let mut placed_menus: HashSet<usize> = HashSet::new();
+        let mut items: Vec<MenuItem<'_>> = Vec::new();
+
+        for node in &self.layout {
+            match node {
+                LayoutNode::Filename(id) => {
+                    if let Some(index) = self.entry_ids.iter().position(|e| e == id) {
+                        if placed_files.insert(index) {
+                            items.push(self.entry_item(index));
+                        }
+                    }
+                }
+                LayoutNode::Menuname(name, _) => {
+                    if let Some(index) = self.submenus.iter().position(|m| m.name == *name) {
+                        if placed_menus.insert(index) {
+                            self.submenus[index].push_into(&mut items);
+                        }
+                    }
+                }
+                LayoutNode::Separator => items.push(MenuItem::Separator),
+                LayoutNode::Merge(merge_type) => {
+                    let mut merged: Vec<(String, Option<usize>, Option<usize>)> = Vec::new();
+
+                    if *merge_type != MergeType::Files {
+                        for (index, submenu) in self.submenus.iter().enumerate() {
+                            if !explicit_menus.contains(submenu.name.as_str())
+                                && placed_menus.insert(index)
+                            {
+                                merged.push((submenu.sort_key(), Some(index), None));
+                            }
+                        }
+                    }
+
+                    if *merge_type != MergeType::Menus {
+                        for (index, id) in self.entry_ids.iter().enumerate() {
+                            if !explicit_files.contains(id.as_str()) && placed_files.insert(index) {
+                                let name = self.entries[index].name().unwrap_or_default();
+                                merged.push((name.to_lowercase(), None, Some(index)));
+                            }
+                        }
+                    }
+
+                    merged.sort_by(|a, b| a.0.cmp(&b.0));
+
+                    for (_, menu_index, entry_index) in merged {
+                        if let Some(index) = menu_index {
+                            self.submenus[index].push_into(&mut items);
+                        } else if let Some(index) = entry_index {
+                            items.push(self.entry_item(index));
+                        }
+                    }
+                }
+            }
+        }
+
+        collapse_separators(items)
+    }
+
+    fn entry_item(&self, index: usize) -> MenuItem<'_> {
+        MenuItem::Entry {
+            id: &self.entry_ids[index],
+            entry: &self.entries[index],
+            alias: None,
+        }
+    }
+
+    /// Add this menu to its parent's item list, either as a
+    /// submenu or inlined according to its layout options
+    fn push_into<'a>(&'a self, items: &mut Vec<MenuItem<'a>>) {
+        if self.is_empty() && !self.options.show_empty {
+            return;
+        }
+
+        if !self.options.inline {
+            items.push(MenuItem::Submenu(self));
+            return;
+        }
+
+        let children = self.layout();
+        let limit = self.options.inline_limit;
+        if limit != 0 && children.len() > limit {
+            items.push(MenuItem::Submenu(self));
+            return;
+        }
+
+        if self.options.inline_alias && children.len() == 1 {
+            if let MenuItem::Entry { id, entry, .. } = children[0] {
+                items.push(MenuItem::Entry {
+                    id,
+                    entry,
+                    alias: Some(self),
+                });
+                return;
+            }
+        }
+
+        if self.options.inline_header {
+            items.push(MenuItem::Header(self));
+        }
+        items.extend(children);
+    }
+
+    fn sort_key(&self) -> String {
+        self.display_name().to_lowercase()
+    }
+}
+
+/// Drop separators at the start and end, and runs of separators
+fn collapse_separators(items: Vec<MenuItem<'_>>) -> Vec<MenuItem<'_>> {
+    let mut collapsed: Vec<MenuItem<'_>> = Vec::new();
+
+    for item in items {
+        let is_separator = matches!(item, MenuItem::Separator);
+        let after_separator = matches!(collapsed.last(), None | Some(MenuItem::Separator));
+
+        if !(is_separator && after_separator) {
+            collapsed.push(item);
+        }
+    }
+
+    if matches!(collapsed.last(), Some(MenuItem::Separator)) {
+        collapsed.pop();
+    }
+
+    collapsed
+}
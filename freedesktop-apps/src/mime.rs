@@ -0,0 +1,396 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use freedesktop_core::info::Info;
+
+use crate::{parser::split_semicolon_list, ApplicationEntry, DesktopFileIndex, ParseError};
+
+const DEFAULT_APPLICATIONS: &str = "Default Applications";
+const ADDED_ASSOCIATIONS: &str = "Added Associations";
+const REMOVED_ASSOCIATIONS: &str = "Removed Associations";
+
+/// Paths of every mimeapps.list file in order of precedence,
+/// as defined by the MIME Applications Associations spec.
+/// Desktop specific files ($desktop-mimeapps.list) come before
+/// the generic file in each directory. Only existing files are
+/// returned.
+pub fn mimeapps_list_paths() -> Vec<PathBuf> {
+    let mut dirs = vec![freedesktop_core::xdg_config_home()];
+    dirs.extend(freedesktop_core::xdg_config_dirs());
+    dirs.push(freedesktop_core::xdg_data_home().join("applications"));
+    dirs.extend(
+        freedesktop_core::xdg_data_dirs()
+            .into_iter()
+            .map(|d| d.join("applications")),
+    );
+
+    desktop_prefixed_paths(&dirs, "mimeapps.list")
+}
+
+/// Guess the MIME type of a file from its name using the glob patterns of
+/// the shared MIME database (mime/globs2 in every data dir). Directories
+/// are inode/directory. Only literal names and `*suffix` patterns are
+/// matched; the highest weight wins, then the longest pattern.
+pub fn mime_type_for_path<P: AsRef<Path>>(path: P) -> Option<String> {
+    let path = path.as_ref();
+    if path.is_dir() {
+        return Some("inode/directory".to_string());
+    }
+
+    let file_name = path.file_name()?.to_string_lossy();
+    let lower_name = file_name.to_lowercase();
+
+    let mut data_dirs = vec![freedesktop_core::xdg_data_home()];
+    data_dirs.extend(freedesktop_core::xdg_data_dirs());
+
+    let mut best: Option<(u32, usize, String)> = None;
+
+    for data_dir in data_dirs {
+        let Ok(content) = std::fs::read_to_string(data_dir.join("mime").join("globs2")) else {
+            continue;
+        };
+
+        // Each line is "weight:mime/type:glob", optionally followed by ":flags"
+        for line in content.lines().filter(|line| !line.starts_with('#')) {
+            let mut fields = line.split(':');
+            let (Some(weight), Some(mime_type), Some(glob)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+            let Ok(weight) = weight.trim().parse::<u32>() else {
+                continue;
+            };
+            let case_sensitive = fields.next().is_some_and(|flags| flags.split(',').any(|f| f == "cs"));
+
+            let name = if case_sensitive { file_name.as_ref() } else { lower_name.as_str() };
+            let glob = if case_sensitive { glob.to_string() } else { glob.to_lowercase() };
+            let matches = match glob.strip_prefix('*') {
+                Some(suffix) if !suffix.contains(['*', '?', '[']) => name.ends_with(suffix),
+                Some(_) => false,
+                None => !glob.contains(['?', '[']) && name == glob,
+            };
+
+            if matches && best.as_ref().is_none_or(|(w, len, _)| (weight, glob.len()) > (*w, *len)) {
+                best = Some((weight, glob.len(), mime_type.to_string()));
+            }
+        }
+    }
+
+    best.map(|(_, _, mime_type)| mime_type)
+}
+
+/// Build `$dir/$desktop-$name` and `$dir/$name` for every directory,
+/// keeping only the files that exist.
+fn desktop_prefixed_paths(dirs: &[PathBuf], name: &str) -> Vec<PathBuf> {
+    let desktops: Vec<String> = Info::current_desktops()
+        .iter()
+        .map(|d| d.to_lowercase())
+        .collect();
+
+    let mut paths = Vec::new();
+    for dir in dirs {
+        for desktop in &desktops {
+            paths.push(dir.join(format!("{}-{}", desktop, name)));
+        }
+        paths.push(dir.join(name));
+    }
+
+    paths.into_iter().filter(|p| p.is_file()).collect()
+}
+
+/// A single parsed mimeapps.list file
+#[derive(Debug, Default, Clone)]
+pub struct MimeAppsList {
+    path: PathBuf,
+    default_applications: HashMap<String, Vec<String>>,
+    added_associations: HashMap<String, Vec<String>>,
+    removed_associations: HashMap<String, Vec<String>>,
+}
+
+impl MimeAppsList {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let content = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| ParseError::IoError(format!("Failed to read file: {}", e)))?;
+
+        let mut list = Self::parse(&content);
+        list.path = path.as_ref().to_path_buf();
+        Ok(list)
+    }
+
+    /// Parse the contents of a mimeapps.list file. Unknown groups
+    /// and malformed lines are ignored, the same way other
+    /// implementations treat this file.
+    pub fn parse(content: &str) -> Self {
+        let mut list = MimeAppsList::default();
+        let mut current_group: Option<&str> = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                current_group = Some(&line[1..line.len() - 1]);
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let target = match current_group {
+                Some(DEFAULT_APPLICATIONS) => &mut list.default_applications,
+                Some(ADDED_ASSOCIATIONS) => &mut list.added_associations,
+                Some(REMOVED_ASSOCIATIONS) => &mut list.removed_associations,
+                _ => continue,
+            };
+
+            target
+                .entry(key.trim().to_lowercase())
+                .or_default()
+                .extend(split_semicolon_list(value.trim()));
+        }
+
+        list
+    }
+
+    /// Get the file path of this list
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Desktop IDs from the [Default Applications] group
+    pub fn default_applications(&self, mime_type: &str) -> &[String] {
+        Self::lookup(&self.default_applications, mime_type)
+    }
+
+    /// Desktop IDs from the [Added Associations] group
+    pub fn added_associations(&self, mime_type: &str) -> &[String] {
+        Self::lookup(&self.added_associations, mime_type)
+    }
+
+    /// Desktop IDs from the [Removed Associations] group
+    pub fn removed_associations(&self, mime_type: &str) -> &[String] {
+        Self::lookup(&self.removed_associations, mime_type)
+    }
+
+    fn lookup<'a>(map: &'a HashMap<String, Vec<String>>, mime_type: &str) -> &'a [String] {
+        map.get(&mime_type.to_lowercase())
+            .map(|ids| ids.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+/// All mimeapps.list files that apply to the current user,
+/// highest precedence first, along with the index used to find
+/// the applications they name.
+#[derive(Debug, Default, Clone)]
+pub struct MimeApps {
+    lists: Vec<MimeAppsList>,
+    index: OnceLock<DesktopFileIndex>,
+}
+
+impl MimeApps {
+    /// Load every mimeapps.list from the standard locations.
+    /// Files that can't be read are skipped.
+    pub fn load() -> Self {
+        let lists = mimeapps_list_paths()
+            .iter()
+            .filter_map(|p| MimeAppsList::from_path(p).ok())
+            .collect();
+
+        Self::from_lists(lists)
+    }
+
+    /// Build from already parsed lists, highest precedence first
+    pub fn from_lists(lists: Vec<MimeAppsList>) -> Self {
+        Self {
+            lists,
+            index: OnceLock::new(),
+        }
+    }
+
+    /// Find applications in this index instead of the XDG data dirs
+    pub fn with_index(mut self, index: DesktopFileIndex) -> Self {
+        self.index = OnceLock::from(index);
+        self
+    }
+
+    pub fn lists(&self) -> &[MimeAppsList] {
+        &self.lists
+    }
+
+    /// The index applications are looked up in. Unless one was given with
+    /// with_index(), the XDG data dirs are indexed on first use and the
+    /// result is reused by every lookup.
+    pub fn index(&self) -> &DesktopFileIndex {
+        self.index.get_or_init(DesktopFileIndex::load)
+    }
+
+    /// Find the default application for a MIME type.
+    ///
+    /// Per the spec, each list is visited in order of precedence and the
+    /// first installed entry from [Default Applications] wins. An ID that
+    /// was removed in the same or a more important list is skipped.
+    /// When no default is set, the most preferred associated application
+    /// is used instead.
+    pub fn default_application(&self, mime_type: &str) -> Option<ApplicationEntry> {
+        let mut removed: Vec<&String> = Vec::new();
+
+        for list in &self.lists {
+            removed.extend(list.removed_associations(mime_type));
+
+            for id in list.default_applications(mime_type) {
+                if removed.contains(&id) {
+                    continue;
+                }
+
+                if let Ok(entry) = self.index().find(id) {
+                    return Some(entry);
+                }
+            }
+        }
+
+        self.associated_applications(mime_type).into_iter().next()
+    }
+
+    /// All applications associated with a MIME type, most preferred first.
+    ///
+    /// Added associations from mimeapps.list come first, followed by
+    /// installed applications listing the type in their MimeType key.
+    /// Removed associations are filtered out of both.
+    pub fn associated_applications(&self, mime_type: &str) -> Vec<ApplicationEntry> {
+        let mut removed: Vec<&String> = Vec::new();
+        let mut ids: Vec<String> = Vec::new();
+
+        for list in &self.lists {
+            removed.extend(list.removed_associations(mime_type));
+
+            for id in list.added_associations(mime_type) {
+                if !removed.contains(&id) && !ids.contains(id) {
+                    ids.push(id.clone());
+                }
+            }
+        }
+
+        let mut entries: Vec<ApplicationEntry> = ids
+            .iter()
+            .filter_map(|id| self.index().find(id).ok())
+            .collect();
+
+        for entry in self.index().applications() {
+            let Some(id) = entry.id() else {
+                continue;
+            };
+
+            let desktop_id = format!("{}.desktop", id);
+            if removed.contains(&&desktop_id) || ids.contains(&desktop_id) {
+                continue;
+            }
+
+            if entry.supports_mime_type(mime_type) {
+                ids.push(desktop_id);
+                entries.push(entry);
+            }
+        }
+
+        entries
+    }
+}
+
+/// Paths of every xdg-terminals.list file in order of precedence,
+/// as defined by the proposed default terminal spec used by
+/// xdg-terminal-exec. Only existing files are returned.
+pub fn xdg_terminals_list_paths() -> Vec<PathBuf> {
+    let mut dirs = vec![freedesktop_core::xdg_config_home()];
+    dirs.extend(freedesktop_core::xdg_config_dirs());
+
+    desktop_prefixed_paths(&dirs, "xdg-terminals.list")
+}
+
+impl ApplicationEntry {
+    /// Check if this application lists the MIME type in its MimeType key
+    pub fn supports_mime_type(&self, mime_type: &str) -> bool {
+        self.mime_types()
+            .is_some_and(|types| types.iter().any(|t| t.eq_ignore_ascii_case(mime_type)))
+    }
+
+    /// Get the default application for a MIME type
+    pub fn default_for_mime_type(mime_type: &str) -> Option<ApplicationEntry> {
+        MimeApps::load().default_application(mime_type)
+    }
+
+    /// Get all applications that can open a MIME type, most preferred first
+    pub fn all_for_mime_type(mime_type: &str) -> Vec<ApplicationEntry> {
+        MimeApps::load().associated_applications(mime_type)
+    }
+
+    /// Get the default handler for a URL scheme, e.g. "steam" or "mailto".
+    /// Handlers are registered as the x-scheme-handler/<scheme> MIME type.
+    pub fn default_for_scheme(scheme: &str) -> Option<ApplicationEntry> {
+        Self::default_for_mime_type(&scheme_mime_type(scheme))
+    }
+
+    /// Get the default web browser (the http handler, then https)
+    pub fn default_web_browser() -> Option<ApplicationEntry> {
+        let mime_apps = MimeApps::load();
+
+        ["http", "https"]
+            .iter()
+            .find_map(|scheme| mime_apps.default_application(&scheme_mime_type(scheme)))
+    }
+
+    /// Get the default mail client (the mailto handler)
+    pub fn default_mail_client() -> Option<ApplicationEntry> {
+        Self::default_for_scheme("mailto")
+    }
+
+    /// Get the default file manager (the inode/directory handler)
+    pub fn default_file_manager() -> Option<ApplicationEntry> {
+        Self::default_for_mime_type("inode/directory")
+    }
+
+    /// Get the preferred terminal emulator.
+    ///
+    /// Desktop IDs listed in xdg-terminals.list are tried first. Otherwise
+    /// the first visible application in the TerminalEmulator category is used.
+    pub fn default_terminal() -> Option<ApplicationEntry> {
+        let index = DesktopFileIndex::load();
+
+        for path in xdg_terminals_list_paths() {
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            for line in content.lines() {
+                let line = line.trim();
+
+                // Lines starting with '-' exclude an entry, which only
+                // matters for the fallback ordering. Skip them here.
+                if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+                    continue;
+                }
+
+                let id = line.trim_start_matches('+');
+                if let Ok(entry) = index.find(id) {
+                    return Some(entry);
+                }
+            }
+        }
+
+        index.applications().into_iter().find(|entry| {
+            entry.should_show()
+                && entry
+                    .categories()
+                    .is_some_and(|c| c.iter().any(|c| c == "TerminalEmulator"))
+        })
+    }
+}
+
+fn scheme_mime_type(scheme: &str) -> String {
+    format!("x-scheme-handler/{}", scheme.to_lowercase())
+}
@@ -1,11 +1,13 @@
-use regex::Regex;
 use std::{
-    collections::HashMap,
     path::{Path, PathBuf},
-    fs::File,
-    io::{BufRead, BufReader},
+    sync::OnceLock,
 };
 
+use crate::borrowed::{BorrowedGroup, Layout};
+use crate::document::DesktopFile;
+use crate::schema::{self, KeyType};
+use crate::validate::{Diagnostic, Severity};
+
 #[derive(Debug, Clone)]
 pub enum ParseError {
     IoError(String),
@@ -28,15 +30,51 @@ impl std::error::Error for ParseError {}
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValueType {
     String(String),
-    #[allow(dead_code)] // Reserved for future localization features
     LocaleString(String),
-    #[allow(dead_code)] // Reserved for future icon handling
     IconString(String),
     Boolean(bool),
     Numeric(f64),
     StringList(Vec<String>),
-    #[allow(dead_code)] // Reserved for future localization features
     LocaleStringList(Vec<String>),
+    /// The escaped text of a key the schema doesn't know, such as X- keys,
+    /// or of a value that isn't valid for its type. The accessors type it.
+    Raw(String),
+}
+
+impl ValueType {
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            ValueType::String(s) | ValueType::LocaleString(s) | ValueType::IconString(s) => {
+                Some(s.clone())
+            }
+            ValueType::Raw(raw) => Some(unescape_value(raw)),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ValueType::Boolean(b) => Some(*b),
+            ValueType::Raw(raw) => parse_boolean(raw),
+            _ => None,
+        }
+    }
+
+    pub fn as_numeric(&self) -> Option<f64> {
+        match self {
+            ValueType::Numeric(n) => Some(*n),
+            ValueType::Raw(raw) => unescape_value(raw).parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<Vec<String>> {
+        match self {
+            ValueType::StringList(list) | ValueType::LocaleStringList(list) => Some(list.clone()),
+            ValueType::Raw(raw) => Some(split_semicolon_list(raw)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -66,240 +104,298 @@ impl LocalizedKey {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct DesktopEntryGroup {
-    #[allow(dead_code)] // Reserved for future group name tracking
-    pub name: String,
-    pub fields: HashMap<String, ValueType>,
-    pub localized_fields: HashMap<String, HashMap<String, ValueType>>,
+/// A group of a DesktopEntry. Values are typed by the schema when they're read.
+#[derive(Debug, Clone, Copy)]
+pub struct DesktopEntryGroup<'a> {
+    group: BorrowedGroup<'a>,
 }
 
-impl DesktopEntryGroup {
-    pub fn new<S: Into<String>>(name: S) -> Self {
-        Self {
-            name: name.into(),
-            fields: HashMap::new(),
-            localized_fields: HashMap::new(),
-        }
+impl<'a> DesktopEntryGroup<'a> {
+    pub fn name(&self) -> &'a str {
+        self.group.name()
     }
 
-    pub fn insert_field(&mut self, key: &str, value: ValueType) {
-        let localized_key = LocalizedKey::parse(key);
-        
-        if let Some(locale) = localized_key.locale {
-            self.localized_fields
-                .entry(localized_key.key)
-                .or_default()
-                .insert(locale, value);
-        } else {
-            self.fields.insert(localized_key.key, value);
-        }
+    /// The keys and raw values of the group
+    pub fn raw(&self) -> BorrowedGroup<'a> {
+        self.group
     }
 
-    pub fn get_field(&self, key: &str) -> Option<&ValueType> {
-        self.fields.get(key)
+    pub fn get_field(&self, key: &str) -> Option<ValueType> {
+        self.group
+            .get(key)
+            .map(|value| parse_value(self.name(), key, value.raw()))
     }
 
-    pub fn get_localized_field(&self, key: &str, locale: Option<&str>) -> Option<&ValueType> {
-        if let Some(locale) = locale {
-            if let Some(localized_map) = self.localized_fields.get(key) {
-                // Try exact match first
-                if let Some(value) = localized_map.get(locale) {
-                    return Some(value);
-                }
-                
-                // Try fallback logic according to spec
-                if let Some(value) = self.try_locale_fallback(localized_map, locale) {
-                    return Some(value);
-                }
-            }
-        }
-        
-        // Fall back to non-localized version
-        self.fields.get(key)
+    pub fn get_localized_field(&self, key: &str, locale: Option<&str>) -> Option<ValueType> {
+        self.group
+            .get_localized(key, locale)
+            .map(|value| parse_value(self.name(), key, value.raw()))
     }
 
-    fn try_locale_fallback<'a>(&self, localized_map: &'a HashMap<String, ValueType>, locale: &str) -> Option<&'a ValueType> {
-        // Strip encoding part if present (everything after '.')
-        let locale_without_encoding = if let Some(dot_pos) = locale.find('.') {
-            &locale[..dot_pos]
-        } else {
-            locale
-        };
-        
-        // Parse locale components: lang_COUNTRY@MODIFIER
-        let (lang, country, modifier) = Self::parse_locale_components(locale_without_encoding);
-        
-        // Follow the spec fallback order exactly:
-        // For lang_COUNTRY@MODIFIER: try lang_COUNTRY@MODIFIER, lang_COUNTRY, lang@MODIFIER, lang, default
-        // For lang_COUNTRY: try lang_COUNTRY, lang, default  
-        // For lang@MODIFIER: try lang@MODIFIER, lang, default
-        // For lang: try lang, default
-        
-        if let (Some(country), Some(modifier)) = (country, modifier) {
-            // Try lang_COUNTRY@MODIFIER
-            let full_locale = format!("{}_{}{}", lang, country, modifier);
-            if let Some(value) = localized_map.get(&full_locale) {
-                return Some(value);
-            }
-            
-            // Try lang_COUNTRY
-            let lang_country = format!("{}_{}", lang, country);
-            if let Some(value) = localized_map.get(&lang_country) {
-                return Some(value);
-            }
-            
-            // Try lang@MODIFIER
-            let lang_modifier = format!("{}{}", lang, modifier);
-            if let Some(value) = localized_map.get(&lang_modifier) {
-                return Some(value);
-            }
-        } else if let Some(country) = country {
-            // Try lang_COUNTRY
-            let lang_country = format!("{}_{}", lang, country);
-            if let Some(value) = localized_map.get(&lang_country) {
-                return Some(value);
-            }
-        } else if let Some(modifier) = modifier {
-            // Try lang@MODIFIER
-            let lang_modifier = format!("{}{}", lang, modifier);
-            if let Some(value) = localized_map.get(&lang_modifier) {
-                return Some(value);
+    /// Every translation of a key as (locale, value), in file order
+    pub fn translations<'k>(&self, key: &'k str) -> impl Iterator<Item = (String, ValueType)> + 'k
+    where
+        'a: 'k,
+    {
+        let name = self.name();
+        self.group.entries().filter_map(move |(localized, value)| {
+            let localized = LocalizedKey::parse(localized);
+            if localized.key != key {
+                return None;
             }
+            Some((localized.locale?, parse_value(name, key, value.raw())))
+        })
+    }
+}
+
+/// The locales to look up for a locale, most specific first. After an exact
+/// match, the spec's order is followed with any encoding removed:
+/// lang_COUNTRY@MODIFIER, lang_COUNTRY, lang@MODIFIER, lang
+pub(crate) fn locale_fallbacks(locale: &str) -> Vec<String> {
+    let mut candidates = vec![locale.to_string()];
+
+    // Strip encoding part if present (everything after '.'), keeping the modifier
+    let without_encoding = match locale.split_once('.') {
+        Some((base, rest)) => match rest.find('@') {
+            Some(at_pos) => format!("{}{}", base, &rest[at_pos..]),
+            None => base.to_string(),
+        },
+        None => locale.to_string(),
+    };
+
+    let (base, modifier) = match without_encoding.find('@') {
+        Some(at_pos) => without_encoding.split_at(at_pos),
+        None => (without_encoding.as_str(), ""),
+    };
+    let (lang, country) = match base.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (base, None),
+    };
+
+    if let Some(country) = country {
+        if !modifier.is_empty() {
+            candidates.push(format!("{}_{}{}", lang, country, modifier));
         }
-        
-        // Try just lang
-        localized_map.get(lang)
+        candidates.push(format!("{}_{}", lang, country));
     }
-    
-    fn parse_locale_components(locale: &str) -> (&str, Option<&str>, Option<&str>) {
-        let (base, modifier) = if let Some(at_pos) = locale.find('@') {
-            (&locale[..at_pos], Some(&locale[at_pos..]))
-        } else {
-            (locale, None)
-        };
-        
-        let (lang, country) = if let Some(under_pos) = base.find('_') {
-            (&base[..under_pos], Some(&base[under_pos + 1..]))
-        } else {
-            (base, None)
-        };
-        
-        (lang, country, modifier)
+    if !modifier.is_empty() {
+        candidates.push(format!("{}{}", lang, modifier));
+    }
+    candidates.push(lang.to_string());
+
+    candidates.dedup();
+    candidates
+}
+
+/// How strictly desktop files are parsed.
+///
+/// Strict parsing fails on any line that isn't valid. Lenient parsing skips
+/// such lines, drops a byte order mark and reads lines that aren't UTF-8 as
+/// Latin-1, recording a warning for each. Entries without the required
+/// keys fail in both modes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub lenient: bool,
+}
+
+impl ParseOptions {
+    pub fn strict() -> Self {
+        Self { lenient: false }
+    }
+
+    pub fn lenient() -> Self {
+        Self { lenient: true }
     }
 }
 
-#[derive(Debug, Default)]
+/// A parsed desktop file. The text is kept along with where its groups
+/// and keys are, and values are only unescaped and typed when read.
+#[derive(Debug, Default, Clone)]
 pub struct DesktopEntry {
     pub path: PathBuf,
-    pub groups: HashMap<String, DesktopEntryGroup>,
+    /// The file as it was read, without a byte order mark
+    source: String,
+    /// Where the groups and keys of the source are
+    layout: Layout,
+    /// The file as written, for editing and lossless saving. Only
+    /// built from the source when it's needed.
+    document: OnceLock<DesktopFile>,
+    pub options: ParseOptions,
+    /// Problems skipped over in lenient mode
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl DesktopEntry {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
-        let file = File::open(path.as_ref())
-            .map_err(|e| ParseError::IoError(format!("Failed to open file: {}", e)))?;
-        let reader = BufReader::new(file);
-        
-        let group_header_regex = Regex::new(r"^\[([^\[\]]+)\]$")
-            .map_err(|e| ParseError::InvalidFormat(format!("Regex error: {}", e)))?;
-
-        let mut current_group: Option<String> = None;
-        let mut entry = DesktopEntry { 
-            path: path.as_ref().to_path_buf(), 
-            ..Default::default() 
-        };
-        
-        for (line_num, line) in reader.lines().enumerate() {
-            let line = line.map_err(|e| ParseError::IoError(format!("Failed to read line {}: {}", line_num + 1, e)))?;
-            let line = line.trim();
-
-            // Skip empty lines and comments
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
+        Self::from_path_with_options(path, &ParseOptions::strict())
+    }
 
-            // Check for group header
-            if let Some(captures) = group_header_regex.captures(line) {
-                let group_name = captures[1].to_string();
-                current_group = Some(group_name.clone());
-                entry.groups.entry(group_name.clone())
-                    .or_insert_with(|| DesktopEntryGroup::new(group_name));
-                continue;
-            }
+    pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, ParseError> {
+        let content = std::fs::read(path.as_ref())
+            .map_err(|e| ParseError::IoError(format!("Failed to read file: {}", e)))?;
 
-            // Parse key-value pair
-            if let Some(eq_pos) = line.find('=') {
-                let key = line[..eq_pos].trim();
-                let value = line[eq_pos + 1..].trim();
+        let mut warnings = Vec::new();
+        let content = decode(content, options, &mut warnings)?;
+        Self::from_source(content, path.as_ref(), options, warnings)
+    }
 
-                if key.is_empty() {
-                    continue; // Skip invalid entries
-                }
+    /// Parse the bytes of a desktop file, which must be UTF-8
+    pub fn from_bytes(content: &[u8], path: &Path) -> Result<Self, ParseError> {
+        Self::from_bytes_with_options(content, path, &ParseOptions::strict())
+    }
 
-                if !is_valid_key_name(key) {
-                    return Err(ParseError::InvalidFormat(format!("Invalid key name: {}", key)));
-                }
+    pub fn from_bytes_with_options(content: &[u8], path: &Path, options: &ParseOptions) -> Result<Self, ParseError> {
+        let mut warnings = Vec::new();
+        let content = decode(content.to_vec(), options, &mut warnings)?;
+        Self::from_source(content, path, options, warnings)
+    }
 
-                if let Some(ref group_name) = current_group {
-                    let parsed_value = parse_value(value)?;
-                    if let Some(group) = entry.groups.get_mut(group_name) {
-                        group.insert_field(key, parsed_value);
-                    }
-                } else {
-                    return Err(ParseError::InvalidFormat("Key-value pair found before any group header".to_string()));
-                }
-            }
+    pub fn parse(content: &str, path: &Path) -> Result<Self, ParseError> {
+        Self::parse_with_options(content, path, &ParseOptions::strict())
+    }
+
+    pub fn parse_with_options(content: &str, path: &Path, options: &ParseOptions) -> Result<Self, ParseError> {
+        Self::from_source(content.to_string(), path, options, Vec::new())
+    }
+
+    fn from_source(mut source: String, path: &Path, options: &ParseOptions, mut warnings: Vec<Diagnostic>) -> Result<Self, ParseError> {
+        if options.lenient && source.starts_with('\u{feff}') {
+            warnings.push(warning(1, 1, "byte-order-mark", "File starts with a byte order mark"));
+            source.drain(..'\u{feff}'.len_utf8());
         }
 
+        let layout = Layout::scan(&source, options, &mut warnings)?;
+        warnings.sort_by_key(|w| (w.line, w.column));
+
+        let entry = DesktopEntry {
+            path: path.to_path_buf(),
+            source,
+            layout,
+            document: OnceLock::new(),
+            options: options.clone(),
+            warnings,
+        };
+
         // Validate required keys
         entry.validate()?;
-        
+
         Ok(entry)
     }
 
+    /// The file as written, parsed from the source the first time it's needed
+    pub fn document(&self) -> &DesktopFile {
+        self.document.get_or_init(|| DesktopFile::parse(&self.source))
+    }
+
+    /// The document for editing. Call reload_document() after changing it.
+    pub fn document_mut(&mut self) -> &mut DesktopFile {
+        self.document();
+        self.document.get_mut().expect("document was just initialized")
+    }
+
+    /// Find the groups and keys again after the document was edited
+    pub fn reload_document(&mut self) -> Result<(), ParseError> {
+        let source = self.document().to_string();
+        let mut warnings = Vec::new();
+        self.layout = Layout::scan(&source, &self.options, &mut warnings)?;
+        self.source = source;
+        self.warnings = warnings;
+        Ok(())
+    }
+
     fn validate(&self) -> Result<(), ParseError> {
-        let desktop_entry = self.groups.get("Desktop Entry")
-            .ok_or_else(|| ParseError::MissingRequiredKey("Desktop Entry group is required".to_string()))?;
+        let desktop_entry = self.get_desktop_entry_group()
+            .ok_or_else(|| ParseError::MissingRequiredKey("Desktop Entry group is required".to_string()))?
+            .raw();
 
         // Type is required
-        let entry_type = desktop_entry.get_field("Type")
-            .ok_or_else(|| ParseError::MissingRequiredKey("Type key is required".to_string()))?;
+        let entry_type = desktop_entry.get("Type")
+            .ok_or_else(|| ParseError::MissingRequiredKey("Type key is required".to_string()))?
+            .as_str();
 
         // Name is required
-        desktop_entry.get_field("Name")
+        desktop_entry.get("Name")
             .ok_or_else(|| ParseError::MissingRequiredKey("Name key is required".to_string()))?;
 
         // For Application type, Exec is required unless DBusActivatable=true
-        if let ValueType::String(type_val) = entry_type {
-            if type_val == "Application" {
-                let dbus_activatable = desktop_entry.get_field("DBusActivatable")
-                    .and_then(|v| match v {
-                        ValueType::Boolean(b) => Some(*b),
-                        _ => None,
-                    })
-                    .unwrap_or(false);
-
-                if !dbus_activatable {
-                    desktop_entry.get_field("Exec")
-                        .ok_or_else(|| ParseError::MissingRequiredKey("Exec key is required for Application type".to_string()))?;
-                }
-            } else if type_val == "Link" {
-                // URL is required for Link type
-                desktop_entry.get_field("URL")
-                    .ok_or_else(|| ParseError::MissingRequiredKey("URL key is required for Link type".to_string()))?;
+        if entry_type == "Application" {
+            let dbus_activatable = desktop_entry.get("DBusActivatable")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            if !dbus_activatable {
+                desktop_entry.get("Exec")
+                    .ok_or_else(|| ParseError::MissingRequiredKey("Exec key is required for Application type".to_string()))?;
             }
+        } else if entry_type == "Link" {
+            // URL is required for Link type
+            desktop_entry.get("URL")
+                .ok_or_else(|| ParseError::MissingRequiredKey("URL key is required for Link type".to_string()))?;
         }
 
         Ok(())
     }
 
-    pub fn get_desktop_entry_group(&self) -> Option<&DesktopEntryGroup> {
-        self.groups.get("Desktop Entry")
+    pub fn get_group(&self, name: &str) -> Option<DesktopEntryGroup<'_>> {
+        self.layout
+            .group(&self.source, name)
+            .map(|group| DesktopEntryGroup { group })
+    }
+
+    /// All groups in file order
+    pub fn get_groups(&self) -> impl Iterator<Item = DesktopEntryGroup<'_>> {
+        self.layout
+            .groups(&self.source)
+            .map(|group| DesktopEntryGroup { group })
+    }
+
+    pub fn get_desktop_entry_group(&self) -> Option<DesktopEntryGroup<'_>> {
+        self.get_group("Desktop Entry")
+    }
+}
+
+/// Decode desktop file bytes. Lenient mode reads lines that aren't UTF-8 as Latin-1.
+fn decode(content: Vec<u8>, options: &ParseOptions, warnings: &mut Vec<Diagnostic>) -> Result<String, ParseError> {
+    let content = match String::from_utf8(content) {
+        Ok(decoded) => return Ok(decoded),
+        Err(e) if !options.lenient => {
+            return Err(ParseError::InvalidFormat(format!("Invalid UTF-8 after byte {}", e.utf8_error().valid_up_to())))
+        }
+        Err(e) => e.into_bytes(),
+    };
+
+    let mut decoded = String::with_capacity(content.len());
+    for (index, line) in content.split_inclusive(|&b| b == b'\n').enumerate() {
+        match std::str::from_utf8(line) {
+            Ok(line) => decoded.push_str(line),
+            Err(e) => {
+                let column = String::from_utf8_lossy(&line[..e.valid_up_to()]).chars().count() + 1;
+                warnings.push(warning(index + 1, column, "invalid-utf8", "Line is not valid UTF-8, read as Latin-1"));
+                decoded.extend(line.iter().map(|&b| b as char));
+            }
+        }
+    }
+
+    Ok(decoded)
+}
+
+pub(crate) fn warning(line: usize, column: usize, rule: &'static str, message: &str) -> Diagnostic {
+    Diagnostic {
+        line,
+        column,
+        severity: Severity::Warning,
+        rule,
+        message: message.to_string(),
     }
 }
 
-fn is_valid_key_name(key: &str) -> bool {
+/// The name of a trimmed "[Group Name]" line. Names can't contain brackets.
+pub(crate) fn group_header(line: &str) -> Option<&str> {
+    let name = line.strip_prefix('[')?.strip_suffix(']')?;
+    (!name.is_empty() && !name.contains(['[', ']'])).then_some(name)
+}
+
+pub(crate) fn is_valid_key_name(key: &str) -> bool {
     // Remove locale part for validation
     let base_key = if let Some(bracket_pos) = key.find('[') {
         &key[..bracket_pos]
@@ -311,33 +407,52 @@ fn is_valid_key_name(key: &str) -> bool {
     base_key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
 }
 
-fn parse_value(value: &str) -> Result<ValueType, ParseError> {
-    // Handle escape sequences
-    let unescaped = unescape_value(value);
-    
-    // Try to parse as boolean first
-    match unescaped.to_lowercase().as_str() {
-        "true" => return Ok(ValueType::Boolean(true)),
-        "false" => return Ok(ValueType::Boolean(false)),
-        _ => {}
+/// Type a value by the schema of its key. Keys the schema doesn't
+/// know are kept raw, to be typed by whoever reads them.
+fn parse_value(group: &str, key: &str, value: &str) -> ValueType {
+    let key = LocalizedKey::parse(key).key;
+    let spec = if group == "Desktop Entry" {
+        schema::desktop_entry_key(&key)
+    } else if group.starts_with("Desktop Action ") {
+        schema::desktop_action_key(&key)
+    } else {
+        None
+    };
+
+    match spec {
+        Some(spec) => parse_typed(value, spec.key_type),
+        None => ValueType::Raw(value.to_string()),
     }
-    
-    // Try to parse as numeric
-    if let Ok(num) = unescaped.parse::<f64>() {
-        return Ok(ValueType::Numeric(num));
+}
+
+/// Parse a raw value as a type, keeping it raw if it isn't valid for the type
+pub(crate) fn parse_typed(value: &str, key_type: KeyType) -> ValueType {
+    match key_type {
+        KeyType::String => ValueType::String(unescape_value(value)),
+        KeyType::LocaleString => ValueType::LocaleString(unescape_value(value)),
+        KeyType::IconString => ValueType::IconString(unescape_value(value)),
+        KeyType::Boolean => parse_boolean(value)
+            .map(ValueType::Boolean)
+            .unwrap_or_else(|| ValueType::Raw(value.to_string())),
+        KeyType::Numeric => unescape_value(value)
+            .parse()
+            .map(ValueType::Numeric)
+            .unwrap_or_else(|_| ValueType::Raw(value.to_string())),
+        KeyType::StringList => ValueType::StringList(split_semicolon_list(value)),
+        KeyType::LocaleStringList => ValueType::LocaleStringList(split_semicolon_list(value)),
     }
-    
-    // Check if it's a list (contains unescaped semicolons)
-    if value.contains(';') {
-        let items = split_semicolon_list(value);
-        return Ok(ValueType::StringList(items));
+}
+
+pub(crate) fn parse_boolean(value: &str) -> Option<bool> {
+    // The spec only allows lowercase, but other casings are common enough
+    match value.to_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
     }
-    
-    // Default to string
-    Ok(ValueType::String(unescaped))
 }
 
-fn unescape_value(value: &str) -> String {
+pub(crate) fn unescape_value(value: &str) -> String {
     let mut result = String::new();
     let mut chars = value.chars();
     
@@ -368,7 +483,7 @@ fn unescape_value(value: &str) -> String {
     result
 }
 
-fn split_semicolon_list(value: &str) -> Vec<String> {
+pub(crate) fn split_semicolon_list(value: &str) -> Vec<String> {
     let mut result = Vec::new();
     let mut current_item = String::new();
     let mut chars = value.chars().peekable();
@@ -428,14 +543,43 @@ mod tests {
 
     #[test]
     fn test_value_parsing() {
-        assert_eq!(parse_value("true").unwrap(), ValueType::Boolean(true));
-        assert_eq!(parse_value("false").unwrap(), ValueType::Boolean(false));
-        assert_eq!(parse_value("123.45").unwrap(), ValueType::Numeric(123.45));
-        assert_eq!(parse_value("hello").unwrap(), ValueType::String("hello".to_string()));
+        let entry = |key, value| parse_value("Desktop Entry", key, value);
+
+        assert_eq!(entry("Terminal", "true"), ValueType::Boolean(true));
+        assert_eq!(entry("Hidden", "false"), ValueType::Boolean(false));
+        assert_eq!(entry("Terminal", "yes"), ValueType::Raw("yes".to_string()));
+        assert_eq!(entry("Name", "2048"), ValueType::LocaleString("2048".to_string()));
+        assert_eq!(entry("Name[de]", "True"), ValueType::LocaleString("True".to_string()));
+        assert_eq!(entry("Comment", "a; b"), ValueType::LocaleString("a; b".to_string()));
+        assert_eq!(entry("Icon", "app"), ValueType::IconString("app".to_string()));
+        assert_eq!(entry("Exec", "app\\sx"), ValueType::String("app x".to_string()));
+        assert_eq!(
+            entry("Categories", "Utility"),
+            ValueType::StringList(vec!["Utility".to_string()])
+        );
+        assert_eq!(
+            entry("Keywords[de]", "eins;zwei;"),
+            ValueType::LocaleStringList(vec!["eins".to_string(), "zwei".to_string()])
+        );
+        assert_eq!(entry("X-Count", "12"), ValueType::Raw("12".to_string()));
+        assert_eq!(
+            parse_value("Desktop Action new", "Name", "New"),
+            ValueType::LocaleString("New".to_string())
+        );
+        assert_eq!(parse_value("X-Vendor", "Name", "true"), ValueType::Raw("true".to_string()));
+    }
+
+    #[test]
+    fn test_raw_values() {
+        let raw = ValueType::Raw("1.5".to_string());
+        assert_eq!(raw.as_numeric(), Some(1.5));
+        assert_eq!(raw.as_string(), Some("1.5".to_string()));
+        assert_eq!(raw.as_bool(), None);
         assert_eq!(
-            parse_value("one;two;three").unwrap(),
-            ValueType::StringList(vec!["one".to_string(), "two".to_string(), "three".to_string()])
+            ValueType::Raw("a\\;b;c".to_string()).as_list(),
+            Some(vec!["a;b".to_string(), "c".to_string()])
         );
+        assert_eq!(ValueType::Raw("TRUE".to_string()).as_bool(), Some(true));
     }
 
     #[test]
@@ -0,0 +1,168 @@
+/// The value types of the Desktop Entry Specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    String,
+    LocaleString,
+    IconString,
+    Boolean,
+    Numeric,
+    StringList,
+    LocaleStringList,
+}
+
+impl KeyType {
+    /// Whether keys of this type may have a [locale] suffix
+    pub fn is_localizable(&self) -> bool {
+        matches!(
+            self,
+            KeyType::LocaleString | KeyType::IconString | KeyType::LocaleStringList
+        )
+    }
+
+    pub fn is_list(&self) -> bool {
+        matches!(self, KeyType::StringList | KeyType::LocaleStringList)
+    }
+}
+
+/// Which entry types a key may be used in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Applies {
+    All,
+    Application,
+    Link,
+}
+
+/// A key of the [Desktop Entry] group as defined by the spec
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KeySpec {
+    pub name: &'static str,
+    pub key_type: KeyType,
+    pub applies: Applies,
+}
+
+const fn key(name: &'static str, key_type: KeyType, applies: Applies) -> KeySpec {
+    KeySpec {
+        name,
+        key_type,
+        applies,
+    }
+}
+
+pub(crate) const DESKTOP_ENTRY_KEYS: &[KeySpec] = &[
+    key("Type", KeyType::String, Applies::All),
+    key("Version", KeyType::String, Applies::All),
+    key("Name", KeyType::LocaleString, Applies::All),
+    key("GenericName", KeyType::LocaleString, Applies::All),
+    key("NoDisplay", KeyType::Boolean, Applies::All),
+    key("Comment", KeyType::LocaleString, Applies::All),
+    key("Icon", KeyType::IconString, Applies::All),
+    key("Hidden", KeyType::Boolean, Applies::All),
+    key("OnlyShowIn", KeyType::StringList, Applies::All),
+    key("NotShowIn", KeyType::StringList, Applies::All),
+    key("DBusActivatable", KeyType::Boolean, Applies::Application),
+    key("TryExec", KeyType::String, Applies::Application),
+    key("Exec", KeyType::String, Applies::Application),
+    key("Path", KeyType::String, Applies::Application),
+    key("Terminal", KeyType::Boolean, Applies::Application),
+    key("Actions", KeyType::StringList, Applies::Application),
+    key("MimeType", KeyType::StringList, Applies::Application),
+    key("Categories", KeyType::StringList, Applies::Application),
+    key("Implements", KeyType::StringList, Applies::All),
+    key("Keywords", KeyType::LocaleStringList, Applies::Application),
+    key("StartupNotify", KeyType::Boolean, Applies::Application),
+    key("StartupWMClass", KeyType::String, Applies::Application),
+    key("URL", KeyType::String, Applies::Link),
+    key("PrefersNonDefaultGPU", KeyType::Boolean, Applies::Application),
+    key("SingleMainWindow", KeyType::Boolean, Applies::Application),
+];
+
+pub(crate) const DESKTOP_ACTION_KEYS: &[KeySpec] = &[
+    key("Name", KeyType::LocaleString, Applies::All),
+    key("Icon", KeyType::IconString, Applies::All),
+    key("Exec", KeyType::String, Applies::All),
+];
+
+/// Keys from older versions of the spec or KDE extensions that were dropped
+pub(crate) const DEPRECATED_KEYS: &[&str] = &[
+    "Encoding",
+    "MiniIcon",
+    "TerminalOptions",
+    "Protocols",
+    "Extensions",
+    "BinaryPattern",
+    "MapNotify",
+    "SwallowTitle",
+    "SwallowExec",
+    "SortOrder",
+    "FilePattern",
+    "Dev",
+    "FSType",
+    "MountPoint",
+    "ReadOnly",
+    "UnmountIcon",
+    "Patterns",
+    "DefaultApp",
+    "DocPath",
+];
+
+pub(crate) const MAIN_CATEGORIES: &[&str] = &[
+    "AudioVideo",
+    "Audio",
+    "Video",
+    "Development",
+    "Education",
+    "Game",
+    "Graphics",
+    "Network",
+    "Office",
+    "Science",
+    "Settings",
+    "System",
+    "Utility",
+];
+
+pub(crate) const ADDITIONAL_CATEGORIES: &[&str] = &[
+    "Building", "Debugger", "IDE", "GUIDesigner", "Profiling", "RevisionControl",
+    "Translation", "Calendar", "ContactManagement", "Database", "Dictionary", "Chart",
+    "Email", "Finance", "FlowChart", "PDA", "ProjectManagement", "Presentation",
+    "Spreadsheet", "WordProcessor", "2DGraphics", "VectorGraphics", "RasterGraphics",
+    "3DGraphics", "Scanning", "OCR", "Photography", "Publishing", "Viewer", "TextTools",
+    "DesktopSettings", "HardwareSettings", "Printing", "PackageManager", "Dialup",
+    "InstantMessaging", "Chat", "IRCClient", "Feed", "FileTransfer", "HamRadio", "News",
+    "P2P", "RemoteAccess", "Telephony", "TelephonyTools", "VideoConference", "WebBrowser",
+    "WebDevelopment", "Midi", "Mixer", "Sequencer", "Tuner", "TV", "AudioVideoEditing",
+    "Player", "Recorder", "DiscBurning", "ActionGame", "AdventureGame", "ArcadeGame",
+    "BoardGame", "BlocksGame", "CardGame", "KidsGame", "LogicGame", "RolePlaying",
+    "Shooter", "Simulation", "SportsGame", "StrategyGame", "Art", "Construction", "Music",
+    "Languages", "ArtificialIntelligence", "Astronomy", "Biology", "Chemistry",
+    "ComputerScience", "DataVisualization", "Economy", "Electricity", "Geography",
+    "Geology", "Geoscience", "History", "Humanities", "ImageProcessing", "Literature",
+    "Maps", "Math", "NumericalAnalysis", "MedicalSoftware", "Physics", "Robotics",
+    "Spirituality", "Sports", "ParallelComputing", "Amusement", "Archiving", "Compression",
+    "Electronics", "Emulator", "Engineering", "FileTools", "FileManager",
+    "TerminalEmulator", "Filesystem", "Monitor", "Security", "Accessibility", "Calculator",
+    "Clock", "TextEditor", "Documentation", "Adult", "Core", "KDE", "GNOME", "XFCE", "DDE",
+    "GTK", "Qt", "Motif", "Java", "ConsoleOnly",
+];
+
+pub(crate) const RESERVED_CATEGORIES: &[&str] = &["Screensaver", "TrayIcon", "Applet", "Shell"];
+
+/// The type the spec gives a key of the [Desktop Entry] group, e.g.
+/// Boolean for "Terminal". X- keys and unknown keys have none.
+pub fn key_type(key: &str) -> Option<KeyType> {
+    desktop_entry_key(key).map(|spec| spec.key_type)
+}
+
+pub(crate) fn desktop_entry_key(name: &str) -> Option<&'static KeySpec> {
+    DESKTOP_ENTRY_KEYS.iter().find(|spec| spec.name == name)
+}
+
+pub(crate) fn desktop_action_key(name: &str) -> Option<&'static KeySpec> {
+    DESKTOP_ACTION_KEYS.iter().find(|spec| spec.name == name)
+}
+
+pub(crate) fn is_known_category(category: &str) -> bool {
+    MAIN_CATEGORIES.contains(&category)
+        || ADDITIONAL_CATEGORIES.contains(&category)
+        || RESERVED_CATEGORIES.contains(&category)
+}
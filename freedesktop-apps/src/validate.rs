@@ -0,0 +1,653 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::parser::unescape_value;
+use crate::schema::{self, Applies, KeySpec, KeyType};
+use crate::{ExecCommand, ExecError, ParseError};
+
+const KNOWN_VERSIONS: &[&str] = &["1.0", "1.1", "1.2", "1.3", "1.4", "1.5"];
+const KNOWN_TYPES: &[&str] = &["Application", "Link", "Directory"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Hint,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Hint => write!(f, "hint"),
+        }
+    }
+}
+
+/// A problem found by validate(). Lines and columns start at 1,
+/// columns count characters rather than bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    /// Stable identifier of the check, e.g. "unknown-key"
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} [{}]",
+            self.line, self.column, self.severity, self.message, self.rule
+        )
+    }
+}
+
+/// Check a desktop file against the Desktop Entry Specification, like
+/// desktop-file-validate does. Every problem is reported, sorted by position.
+pub fn validate(content: &[u8]) -> Vec<Diagnostic> {
+    let mut validator = Validator::default();
+    let groups = validator.scan(content);
+    validator.check_groups(&groups);
+
+    let mut diagnostics = validator.diagnostics;
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// Read and check a desktop file
+pub fn validate_path<P: AsRef<Path>>(path: P) -> Result<Vec<Diagnostic>, ParseError> {
+    let content = std::fs::read(path.as_ref())
+        .map_err(|e| ParseError::IoError(format!("Failed to read file: {}", e)))?;
+    Ok(validate(&content))
+}
+
+struct KeyLine {
+    key: String,
+    locale: Option<String>,
+    value: String,
+    line: usize,
+    key_column: usize,
+    value_column: usize,
+}
+
+struct Group {
+    name: String,
+    line: usize,
+    duplicate: bool,
+    entries: Vec<KeyLine>,
+}
+
+impl Group {
+    /// The value in effect, the last one when a key is repeated like the parser does
+    fn get(&self, key: &str) -> Option<&KeyLine> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.key == key && entry.locale.is_none())
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    fn report(&mut self, line: usize, column: usize, severity: Severity, rule: &'static str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            column,
+            severity,
+            rule,
+            message,
+        });
+    }
+
+    fn error(&mut self, line: usize, column: usize, rule: &'static str, message: String) {
+        self.report(line, column, Severity::Error, rule, message);
+    }
+
+    fn warning(&mut self, line: usize, column: usize, rule: &'static str, message: String) {
+        self.report(line, column, Severity::Warning, rule, message);
+    }
+
+    fn hint(&mut self, line: usize, column: usize, rule: &'static str, message: String) {
+        self.report(line, column, Severity::Hint, rule, message);
+    }
+
+    /// Check the syntax of each line and collect the groups
+    fn scan(&mut self, content: &[u8]) -> Vec<Group> {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut group_lines: HashMap<String, usize> = HashMap::new();
+        let body = content.strip_suffix(b"\n").unwrap_or(content);
+
+        for (index, raw) in body.split(|&b| b == b'\n').enumerate() {
+            let number = index + 1;
+            if content.is_empty() {
+                break;
+            }
+
+            let decoded = String::from_utf8_lossy(raw);
+            if let Err(e) = std::str::from_utf8(raw) {
+                let column = String::from_utf8_lossy(&raw[..e.valid_up_to()]).chars().count() + 1;
+                self.error(number, column, "invalid-utf8", "Line is not valid UTF-8".to_string());
+            }
+
+            let text = decoded.strip_suffix('\r').unwrap_or(&decoded);
+            let line = text.trim_start();
+            let indent = text.chars().count() - line.chars().count();
+            let line = line.trim_end();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) else {
+                    self.error(number, indent + 1, "invalid-group-header", format!("Malformed group header: {}", line));
+                    continue;
+                };
+
+                if name.is_empty() || name.chars().any(|c| c == '[' || c == ']' || c.is_control()) {
+                    self.error(number, indent + 1, "invalid-group-name", format!("Invalid group name: {}", name));
+                }
+
+                let duplicate = group_lines.contains_key(name);
+                if let Some(first) = group_lines.get(name) {
+                    self.error(
+                        number,
+                        indent + 1,
+                        "duplicate-group",
+                        format!("Group [{}] is already defined on line {}", name, first),
+                    );
+                } else if groups.is_empty() && name != "Desktop Entry" {
+                    self.error(
+                        number,
+                        indent + 1,
+                        "first-group",
+                        format!("First group must be [Desktop Entry], found [{}]", name),
+                    );
+                } else if name != "Desktop Entry" && !name.starts_with("Desktop Action ") && !name.starts_with("X-") {
+                    self.error(
+                        number,
+                        indent + 1,
+                        "unknown-group",
+                        format!("Group [{}] is not defined by the spec, extension groups must start with X-", name),
+                    );
+                }
+
+                group_lines.entry(name.to_string()).or_insert(number);
+                groups.push(Group {
+                    name: name.to_string(),
+                    line: number,
+                    duplicate,
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some((key_part, value_part)) = line.split_once('=') else {
+                self.error(
+                    number,
+                    indent + 1,
+                    "invalid-line",
+                    "Line is not a comment, group header or key=value pair".to_string(),
+                );
+                continue;
+            };
+
+            let Some(group) = groups.last_mut() else {
+                self.error(number, indent + 1, "key-before-group", "Key appears before the first group".to_string());
+                continue;
+            };
+
+            let full_key = key_part.trim_end();
+            let value = value_part.trim_start();
+            let key_column = indent + 1;
+            let value_column =
+                indent + key_part.chars().count() + 1 + (value_part.chars().count() - value.chars().count()) + 1;
+
+            let (key, locale) = match full_key.split_once('[') {
+                Some((key, rest)) => match rest.strip_suffix(']') {
+                    Some(locale) => (key, Some(locale)),
+                    None => {
+                        self.error(number, key_column, "invalid-key-name", format!("Invalid key name: {}", full_key));
+                        continue;
+                    }
+                },
+                None => (full_key, None),
+            };
+
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                self.error(number, key_column, "invalid-key-name", format!("Invalid key name: {}", full_key));
+                continue;
+            }
+
+            if let Some(locale) = locale {
+                let valid = !locale.is_empty()
+                    && locale
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '@' | '.' | '-'));
+                if !valid {
+                    self.error(number, key_column, "invalid-locale", format!("Invalid locale in key: {}", full_key));
+                }
+            }
+
+            if let Some(first) = group
+                .entries
+                .iter()
+                .find(|entry| entry.key == key && entry.locale.as_deref() == locale)
+            {
+                self.error(
+                    number,
+                    key_column,
+                    "duplicate-key",
+                    format!("Key {} is already defined on line {}", full_key, first.line),
+                );
+            }
+
+            group.entries.push(KeyLine {
+                key: key.to_string(),
+                locale: locale.map(str::to_string),
+                value: value.to_string(),
+                line: number,
+                key_column,
+                value_column,
+            });
+        }
+
+        if groups.is_empty() && !content.is_empty() {
+            self.error(1, 1, "missing-group", "File has no [Desktop Entry] group".to_string());
+        }
+
+        groups
+    }
+
+    fn check_groups(&mut self, groups: &[Group]) {
+        let Some(main) = groups.iter().find(|g| g.name == "Desktop Entry") else {
+            if !groups.is_empty() {
+                self.error(1, 1, "missing-group", "File has no [Desktop Entry] group".to_string());
+            }
+            return;
+        };
+
+        let entry_type = main.get("Type").map(|t| t.value.as_str());
+        for group in groups.iter().filter(|g| g.name == "Desktop Entry") {
+            self.check_desktop_entry(group, entry_type);
+        }
+
+        let actions: Vec<(usize, String)> = main
+            .get("Actions")
+            .map(|actions| list_items(&actions.value))
+            .unwrap_or_default();
+
+        for group in groups.iter().filter(|g| !g.duplicate) {
+            if let Some(action) = group.name.strip_prefix("Desktop Action ") {
+                if !actions.iter().any(|(_, id)| id == action) {
+                    self.warning(
+                        group.line,
+                        1,
+                        "unused-action-group",
+                        format!("Action {} is not listed in the Actions key", action),
+                    );
+                }
+            }
+        }
+
+        for group in groups.iter().filter(|g| g.name.starts_with("Desktop Action ")) {
+            self.check_action(group);
+        }
+
+        if let Some(line) = main.get("Actions") {
+            for (offset, id) in &actions {
+                let name = format!("Desktop Action {}", id);
+                if !groups.iter().any(|g| g.name == name) {
+                    self.error(
+                        line.line,
+                        line.value_column + offset,
+                        "missing-action-group",
+                        format!("Action {} has no [{}] group", id, name),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_desktop_entry(&mut self, group: &Group, entry_type: Option<&str>) {
+        for entry in &group.entries {
+            if entry.key.starts_with("X-") {
+                continue;
+            }
+
+            if schema::DEPRECATED_KEYS.contains(&entry.key.as_str()) {
+                self.warning(
+                    entry.line,
+                    entry.key_column,
+                    "deprecated-key",
+                    format!("Key {} is deprecated", entry.key),
+                );
+                continue;
+            }
+
+            let Some(spec) = schema::desktop_entry_key(&entry.key) else {
+                self.error(
+                    entry.line,
+                    entry.key_column,
+                    "unknown-key",
+                    format!("Key {} is not defined by the spec, extension keys must start with X-", entry.key),
+                );
+                continue;
+            };
+
+            let applies = match entry_type {
+                Some("Application") => spec.applies != Applies::Link,
+                Some("Link") => spec.applies != Applies::Application,
+                Some("Directory") => spec.applies == Applies::All,
+                _ => true,
+            };
+            if !applies {
+                self.warning(
+                    entry.line,
+                    entry.key_column,
+                    "key-not-applicable",
+                    format!("Key {} is not used in entries of type {}", entry.key, entry_type.unwrap_or_default()),
+                );
+            }
+
+            self.check_value(entry, spec);
+        }
+
+        if group.duplicate {
+            return;
+        }
+
+        match group.get("Type") {
+            None => self.error(group.line, 1, "missing-required-key", "Required key Type is missing".to_string()),
+            Some(t) if !KNOWN_TYPES.contains(&t.value.as_str()) && !t.value.starts_with("X-") => {
+                self.error(t.line, t.value_column, "invalid-type", format!("Unknown entry type: {}", t.value));
+            }
+            Some(_) => {}
+        }
+
+        if group.get("Name").is_none() {
+            self.error(group.line, 1, "missing-required-key", "Required key Name is missing".to_string());
+        }
+
+        let dbus_activatable = group.get("DBusActivatable").is_some_and(|v| v.value == "true");
+        if entry_type == Some("Application") && !dbus_activatable && group.get("Exec").is_none() {
+            self.error(
+                group.line,
+                1,
+                "missing-required-key",
+                "Required key Exec is missing for an Application".to_string(),
+            );
+        }
+        if entry_type == Some("Link") && group.get("URL").is_none() {
+            self.error(group.line, 1, "missing-required-key", "Required key URL is missing for a Link".to_string());
+        }
+
+        if let Some(version) = group.get("Version") {
+            if !KNOWN_VERSIONS.contains(&version.value.as_str()) {
+                self.warning(
+                    version.line,
+                    version.value_column,
+                    "unknown-version",
+                    format!("Unknown version of the spec: {}", version.value),
+                );
+            }
+        }
+
+        if let Some(categories) = group.get("Categories") {
+            self.check_categories(categories);
+        }
+
+        if let (Some(only), Some(not)) = (group.get("OnlyShowIn"), group.get("NotShowIn")) {
+            let not_items: Vec<String> = list_items(&not.value).into_iter().map(|(_, d)| d).collect();
+            for (offset, desktop) in list_items(&only.value) {
+                if not_items.contains(&desktop) {
+                    self.error(
+                        only.line,
+                        only.value_column + offset,
+                        "show-in-conflict",
+                        format!("Desktop {} is in both OnlyShowIn and NotShowIn", desktop),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_action(&mut self, group: &Group) {
+        for entry in &group.entries {
+            if entry.key.starts_with("X-") {
+                continue;
+            }
+
+            match schema::desktop_action_key(&entry.key) {
+                Some(spec) => self.check_value(entry, spec),
+                None => self.error(
+                    entry.line,
+                    entry.key_column,
+                    "unknown-key",
+                    format!("Key {} is not valid in group [{}]", entry.key, group.name),
+                ),
+            }
+        }
+
+        if !group.duplicate && group.get("Name").is_none() {
+            self.error(
+                group.line,
+                1,
+                "missing-required-key",
+                format!("Required key Name is missing in group [{}]", group.name),
+            );
+        }
+    }
+
+    fn check_value(&mut self, entry: &KeyLine, spec: &KeySpec) {
+        if entry.locale.is_some() && !spec.key_type.is_localizable() {
+            self.error(
+                entry.line,
+                entry.key_column,
+                "invalid-localized-key",
+                format!("Key {} can't be localized", entry.key),
+            );
+        }
+
+        match spec.key_type {
+            KeyType::Boolean => match entry.value.as_str() {
+                "true" | "false" => {}
+                "0" | "1" => self.warning(
+                    entry.line,
+                    entry.value_column,
+                    "deprecated-boolean",
+                    format!("Boolean value of {} should be true or false, not {}", entry.key, entry.value),
+                ),
+                _ => self.error(
+                    entry.line,
+                    entry.value_column,
+                    "invalid-boolean",
+                    format!("Value of {} must be true or false, found {}", entry.key, entry.value),
+                ),
+            },
+            KeyType::Numeric => {
+                if entry.value.parse::<f64>().is_err() {
+                    self.error(
+                        entry.line,
+                        entry.value_column,
+                        "invalid-numeric",
+                        format!("Value of {} must be a number, found {}", entry.key, entry.value),
+                    );
+                }
+            }
+            key_type => {
+                self.check_escapes(entry, key_type.is_list());
+                if key_type.is_list() && !entry.value.is_empty() && !entry.value.ends_with(';') {
+                    self.warning(
+                        entry.line,
+                        entry.value_column + entry.value.chars().count(),
+                        "list-missing-semicolon",
+                        format!("List value of {} should end with a semicolon", entry.key),
+                    );
+                }
+            }
+        }
+
+        if entry.key == "Exec" {
+            self.check_exec(entry);
+        }
+    }
+
+    fn check_escapes(&mut self, entry: &KeyLine, list: bool) {
+        let mut chars = entry.value.chars().enumerate();
+
+        while let Some((offset, ch)) = chars.next() {
+            if ch.is_control() {
+                self.error(
+                    entry.line,
+                    entry.value_column + offset,
+                    "invalid-character",
+                    format!("Value of {} contains a control character", entry.key),
+                );
+            } else if ch == '\\' {
+                match chars.next() {
+                    Some((_, 's' | 'n' | 't' | 'r' | '\\')) => {}
+                    Some((_, ';')) if list => {}
+                    next => self.error(
+                        entry.line,
+                        entry.value_column + offset,
+                        "invalid-escape",
+                        format!(
+                            "Invalid escape sequence \\{} in value of {}",
+                            next.map(|(_, c)| c.to_string()).unwrap_or_default(),
+                            entry.key
+                        ),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn check_categories(&mut self, entry: &KeyLine) {
+        let items = list_items(&entry.value);
+
+        for (offset, category) in &items {
+            if !category.starts_with("X-") && !schema::is_known_category(category) {
+                self.error(
+                    entry.line,
+                    entry.value_column + offset,
+                    "invalid-category",
+                    format!("Unregistered category {}, extension categories must start with X-", category),
+                );
+            }
+        }
+
+        if !items
+            .iter()
+            .any(|(_, category)| schema::MAIN_CATEGORIES.contains(&category.as_str()))
+        {
+            self.hint(
+                entry.line,
+                entry.value_column,
+                "missing-main-category",
+                "Categories should include a main category".to_string(),
+            );
+        }
+    }
+
+    fn check_exec(&mut self, entry: &KeyLine) {
+        if let Err(err) = ExecCommand::parse(&unescape_value(&entry.value)) {
+            let (rule, needle) = match &err {
+                ExecError::Empty => ("empty-exec", None),
+                ExecError::UnterminatedQuote => ("unterminated-quote", None),
+                ExecError::ReservedCharacter(c) => ("reserved-character", Some(c.to_string())),
+                ExecError::InvalidEscape(c) => ("invalid-exec-escape", Some(format!("\\{}", c))),
+                ExecError::FieldCodeInQuotes(code) => ("field-code-in-quotes", Some(code.clone())),
+                ExecError::InvalidFieldCode(code) => ("invalid-field-code", Some(code.clone())),
+                ExecError::NotStandalone(code) => ("field-code-not-standalone", Some(code.clone())),
+            };
+            // The error has no position, point at the first place it can come from
+            let offset = needle
+                .and_then(|needle| entry.value.find(&needle))
+                .map(|index| entry.value[..index].chars().count())
+                .unwrap_or(0);
+            self.error(entry.line, entry.value_column + offset, rule, err.to_string());
+        }
+
+        // Lints for commands the launcher accepts
+        let mut chars = entry.value.chars().enumerate();
+        let mut file_code: Option<char> = None;
+
+        while let Some((offset, ch)) = chars.next() {
+            if ch != '%' {
+                continue;
+            }
+
+            let column = entry.value_column + offset;
+            match chars.next().map(|(_, c)| c) {
+                Some(code @ ('f' | 'F' | 'u' | 'U')) => {
+                    if let Some(first) = file_code {
+                        self.error(
+                            entry.line,
+                            column,
+                            "multiple-file-field-codes",
+                            format!("Exec may contain only one of %f, %F, %u and %U, found %{} and %{}", first, code),
+                        );
+                    }
+                    file_code.get_or_insert(code);
+                }
+                Some(code @ ('d' | 'D' | 'n' | 'N' | 'v' | 'm')) => self.warning(
+                    entry.line,
+                    column,
+                    "deprecated-field-code",
+                    format!("Field code %{} is deprecated", code),
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Items of a raw list value with the character offset each starts at
+fn list_items(value: &str) -> Vec<(usize, String)> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    let mut chars = value.chars().enumerate();
+
+    while let Some((offset, ch)) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some((_, ';')) => current.push(';'),
+                Some((_, next)) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            ';' => {
+                if !current.is_empty() {
+                    items.push((start, std::mem::take(&mut current)));
+                }
+                start = offset + 1;
+            }
+            ch => current.push(ch),
+        }
+    }
+
+    if !current.is_empty() {
+        items.push((start, current));
+    }
+
+    items
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::TestDir;
+use freedesktop_apps::{ApplicationEntry, ExecuteError};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn temp_entry(file_name: &str, content: &str) -> ApplicationEntry {
+    let dir = TestDir::new("action");
+    ApplicationEntry::try_from_path(dir.write(file_name, content)).unwrap()
+}
+
+#[test]
+fn test_actions_in_key_order() {
+    let entry = ApplicationEntry::try_from_path(fixture_path("complete_app.desktop")).unwrap();
+    let actions = entry.actions();
+
+    let ids: Vec<&str> = actions.iter().map(|a| a.id()).collect();
+    assert_eq!(ids, vec!["new-window", "preferences"]);
+
+    let new_window = &actions[0];
+    assert_eq!(new_window.get_string("Name").as_deref(), Some("New Window"));
+    assert_eq!(new_window.localized_name("es_ES").as_deref(), Some("Nueva Ventana"));
+    assert_eq!(new_window.icon().as_deref(), Some("window-new"));
+    assert_eq!(new_window.exec().as_deref(), Some("test-app --new-window"));
+
+    assert!(entry.action("preferences").is_some());
+    assert!(entry.action("missing").is_none());
+}
+
+#[test]
+fn test_actions_without_groups_are_skipped() {
+    let entry = temp_entry(
+        "action_missing_group.desktop",
+        "[Desktop Entry]\nType=Application\nName=Test\nExec=test\nActions=missing;present;\n\n[Desktop Action present]\nName=Present\nExec=test --present\n",
+    );
+
+    let ids: Vec<&str> = entry.actions().iter().map(|a| a.id()).collect();
+    assert_eq!(ids, vec!["present"]);
+}
+
+#[test]
+fn test_action_prepare_command() {
+    let entry = temp_entry(
+        "action_prepare.desktop",
+        "[Desktop Entry]\nType=Application\nName=Editor\nExec=editor %F\nActions=open;empty;\n\n[Desktop Action open]\nName=Open\nExec=editor --open %f\n\n[Desktop Action empty]\nName=Empty\n",
+    );
+
+    let commands = entry.action("open").unwrap().prepare_command(&["/tmp/a file.txt", "/tmp/b.txt"], &[]).unwrap();
+    assert_eq!(
+        commands,
+        vec![
+            ("editor".to_string(), vec!["--open".to_string(), "/tmp/a file.txt".to_string()]),
+            ("editor".to_string(), vec!["--open".to_string(), "/tmp/b.txt".to_string()]),
+        ]
+    );
+
+    assert!(matches!(
+        entry.action("empty").unwrap().prepare_command(&[], &[]),
+        Err(ExecuteError::NotExecutable(_))
+    ));
+}
+
+#[test]
+fn test_action_respects_try_exec() {
+    // complete_app.desktop has TryExec=/usr/bin/test-app
+    let entry = ApplicationEntry::try_from_path(fixture_path("complete_app.desktop")).unwrap();
+
+    assert!(matches!(
+        entry.action("new-window").unwrap().prepare_command(&[], &[]),
+        Err(ExecuteError::ValidationFailed(_))
+    ));
+}
@@ -0,0 +1,63 @@
+use freedesktop_apps::{ApplicationEntry, BorrowedDesktopFile, ParseError, ParseOptions};
+use std::borrow::Cow;
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+#[test]
+fn test_matches_full_parser() {
+    let content = fixture("complete_app.desktop");
+    let file = BorrowedDesktopFile::parse(&content).unwrap();
+    let entry = ApplicationEntry::from_str(&content, None).unwrap();
+    let group = file.desktop_entry().unwrap();
+
+    for key in ["Name", "GenericName", "Comment", "Icon", "Exec"] {
+        assert_eq!(group.get(key).map(|v| v.as_str().into_owned()), entry.get_string(key));
+        for locale in ["es", "es_ES.UTF-8", "en_US", "fr_FR@euro", "ja"] {
+            assert_eq!(
+                group.get_localized(key, Some(locale)).map(|v| v.as_str().into_owned()),
+                entry.get_localized_string(key, Some(locale))
+            );
+        }
+    }
+
+    let keywords: Vec<String> = group.get("Keywords").unwrap().as_list().into_iter().map(Cow::into_owned).collect();
+    assert_eq!(Some(keywords), entry.keywords());
+    assert_eq!(group.get("Terminal").unwrap().as_bool(), Some(false));
+
+    let groups: Vec<&str> = file.groups().iter().map(|g| g.name()).collect();
+    assert_eq!(groups, vec!["Desktop Entry", "Desktop Action new-window", "Desktop Action preferences"]);
+}
+
+#[test]
+fn test_lazy_values() {
+    let content = fixture("escape_sequences.desktop");
+    let file = BorrowedDesktopFile::parse(&content).unwrap();
+    let group = file.desktop_entry().unwrap();
+
+    // Values without escapes are borrowed from the text
+    assert!(matches!(group.get("Type").unwrap().as_str(), Cow::Borrowed("Application")));
+    assert!(matches!(group.get("Comment").unwrap().as_str(), Cow::Owned(_)));
+    assert!(group.get("Comment").unwrap().raw().contains("\\n"));
+
+    let categories = group.get("Categories").unwrap().as_list();
+    assert!(categories.contains(&Cow::Borrowed("Test;Category")));
+    assert!(categories.contains(&Cow::Borrowed("Another")));
+
+    let content = fixture("numeric_test.desktop");
+    let file = BorrowedDesktopFile::parse(&content).unwrap();
+    assert_eq!(file.desktop_entry().unwrap().get("X-Test-Integer").unwrap().as_numeric(), Some(42.0));
+}
+
+#[test]
+fn test_strict_and_lenient() {
+    let content = "\u{feff}[Desktop Entry]\nName=App\nBad Key=1\n[Desktop Entry]\nExec=app\n";
+
+    assert!(matches!(BorrowedDesktopFile::parse(content), Err(ParseError::InvalidFormat(_))));
+
+    let file = BorrowedDesktopFile::parse_with_options(content, &ParseOptions::lenient()).unwrap();
+    assert_eq!(file.groups().len(), 1);
+    let keys: Vec<&str> = file.desktop_entry().unwrap().entries().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["Name", "Exec"]);
+}
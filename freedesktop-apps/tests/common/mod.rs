@@ -0,0 +1,89 @@
+//! Fixtures shared by the integration tests. Files are written to
+//! directories unique to the test binary, process and call below
+//! Cargo's target tmp dir, so tests and parallel runs never share them.
+
+// Each test binary only uses some of the helpers
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A fresh directory, removed again when dropped
+pub struct TestDir {
+    root: PathBuf,
+}
+
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!(
+            "{}-{}-{}-{}",
+            env!("CARGO_CRATE_NAME"),
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).unwrap();
+        Self { root }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn join(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// Write a file below the directory, creating its parent directories
+    pub fn write(&self, path: &str, content: &str) -> PathBuf {
+        let path = self.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Write `<dir>/applications/<file>`, an application called `name`
+    pub fn write_app(&self, dir: &str, file: &str, name: &str, extra: &str) -> PathBuf {
+        self.write(
+            &format!("{}/applications/{}", dir, file),
+            &format!("[Desktop Entry]\nType=Application\nName={}\nExec=test\n{}", name, extra),
+        )
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.root).ok();
+    }
+}
+
+/// The tree that the XDG variables of this test binary point into.
+///
+/// The library finds its files through process-wide environment
+/// variables, so they are set in one place, once, before any test of the
+/// binary looks them up. `init` writes the files and returns the variables
+/// to set; relative paths in them, separated by ':', are made absolute.
+/// PATH entries go in front of the inherited PATH.
+pub fn xdg_root(init: impl FnOnce(&TestDir) -> Vec<(&'static str, &'static str)>) -> &'static TestDir {
+    static ROOT: OnceLock<TestDir> = OnceLock::new();
+
+    ROOT.get_or_init(|| {
+        let dir = TestDir::new("xdg");
+        for (key, value) in init(&dir) {
+            if key.ends_with("_HOME") || key.ends_with("_DIRS") || key == "PATH" {
+                let mut paths: Vec<String> = value.split(':').map(|p| dir.join(p).display().to_string()).collect();
+                if key == "PATH" {
+                    paths.extend(std::env::var("PATH").ok());
+                }
+                std::env::set_var(key, paths.join(":"));
+            } else {
+                std::env::set_var(key, value);
+            }
+        }
+        dir
+    })
+}
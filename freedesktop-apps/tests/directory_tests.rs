@@ -0,0 +1,74 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{directory_entry_paths, DirectoryEntry, FindError};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn write_directory(root: &TestDir, dir: &str, file: &str, content: &str) {
+    root.write(&format!("{}/desktop-directories/{}", dir, file), content);
+}
+
+fn setup() -> &'static TestDir {
+    xdg_root(|dir| {
+        write_directory(dir, "system", "Games.directory", "[Desktop Entry]\nType=Directory\nName=System Games\n");
+        write_directory(dir, "system", "Office.directory", "[Desktop Entry]\nType=Directory\nName=Office\n");
+        write_directory(dir, "system", "kde/utilities.directory", "[Desktop Entry]\nType=Directory\nName=Utilities\n");
+        write_directory(dir, "home", "Games.directory", "[Desktop Entry]\nType=Directory\nName=My Games\n");
+        write_directory(dir, "home", "Office.directory", "[Desktop Entry]\nType=Directory\nName=Office\nHidden=true\n");
+        write_directory(dir, "system", "Wine.directory", "[Desktop Entry]\nType=Directory\nName=Wine\nX-Wine Key=1\n");
+        write_directory(dir, "home", "Broken.directory", "Name=No group\n");
+
+        vec![("XDG_DATA_HOME", "home"), ("XDG_DATA_DIRS", "system")]
+    })
+}
+
+#[test]
+fn test_directory_entry_fields() {
+    let entry = DirectoryEntry::from_path(fixture_path("directory_entry.directory"))
+        .expect("Failed to parse directory entry");
+
+    assert_eq!(entry.entry_type().as_deref(), Some("Directory"));
+    assert_eq!(entry.get_string("Name").as_deref(), Some("Test Directory"));
+    assert_eq!(entry.localized_name("es_ES").as_deref(), Some("Directorio de Prueba"));
+    assert_eq!(entry.localized_name("fr").as_deref(), Some("Test Directory"));
+    assert_eq!(entry.localized_comment("es").as_deref(), Some("A test directory entry"));
+    assert_eq!(entry.icon().as_deref(), Some("folder"));
+    assert!(!entry.is_hidden());
+    assert!(!entry.no_display());
+}
+
+#[test]
+fn test_directory_lookup_precedence() {
+    let dir = setup();
+
+    let paths = directory_entry_paths();
+    assert_eq!(paths[0], dir.join("home/desktop-directories"));
+
+    // $XDG_DATA_HOME wins over $XDG_DATA_DIRS
+    let games = DirectoryEntry::from_id("Games.directory").unwrap();
+    assert_eq!(games.get_string("Name").as_deref(), Some("My Games"));
+
+    // Subdirectories are part of the ID, and the extension is optional
+    let utilities = DirectoryEntry::from_id("kde-utilities").unwrap();
+    assert_eq!(utilities.get_string("Name").as_deref(), Some("Utilities"));
+
+    // A hidden user entry masks the system one
+    assert!(matches!(DirectoryEntry::from_id("Office.directory"), Err(FindError::NotFound(_))));
+    assert!(matches!(DirectoryEntry::from_id("Missing.directory"), Err(FindError::NotFound(_))));
+}
+
+#[test]
+fn test_directory_lookup_is_lenient() {
+    let dir = setup();
+
+    let wine = DirectoryEntry::from_id("Wine").unwrap();
+    assert_eq!(wine.get_string("Name").as_deref(), Some("Wine"));
+
+    match DirectoryEntry::from_id("Broken") {
+        Err(FindError::InvalidEntry(path, _)) => assert_eq!(path, dir.join("home/desktop-directories/Broken.directory")),
+        other => panic!("Expected InvalidEntry, got {:?}", other.map(|e| e.name())),
+    }
+}
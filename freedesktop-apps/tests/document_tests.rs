@@ -0,0 +1,83 @@
+use freedesktop_apps::{escape_list, escape_value, ApplicationEntry, DesktopFile};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(format!("{}/tests/fixtures", env!("CARGO_MANIFEST_DIR")))
+}
+
+fn entry_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            entry_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "desktop" || ext == "directory") {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn test_fixtures_round_trip() {
+    let mut files = Vec::new();
+    entry_files(&fixtures_dir(), &mut files);
+    assert!(files.len() > 10);
+
+    for path in files {
+        let content = fs::read_to_string(&path).unwrap();
+        let document = DesktopFile::from_path(&path).unwrap();
+        assert_eq!(document.to_string(), content, "{} did not round-trip", path.display());
+    }
+}
+
+#[test]
+fn test_entry_document_round_trip() {
+    let path = fixtures_dir().join("escape_sequences.desktop");
+    let entry = ApplicationEntry::try_from_path(&path).unwrap();
+
+    assert_eq!(entry.document().to_string(), fs::read_to_string(&path).unwrap());
+    assert_eq!(
+        entry.document().get("Desktop Entry", "Categories"),
+        Some(r"Test\;Category;Another;")
+    );
+}
+
+#[test]
+fn test_edits_keep_comments_and_order() {
+    let mut document = DesktopFile::parse(
+        "# Header\n[Desktop Entry]\nType=Application\n# Name follows\nName=Old\nName[de]=Alt\nExec=app\n\n[Desktop Action new]\nName=New\n",
+    );
+
+    document.set("Desktop Entry", "Name", "New Name");
+    document.set("Desktop Entry", "Name[fr]", "Nouveau");
+    document.set("Desktop Entry", "Comment", &escape_value("Line\nbreak"));
+    assert!(document.remove("Desktop Entry", "Type"));
+    assert!(!document.remove("Desktop Entry", "Missing"));
+
+    assert_eq!(
+        document.to_string(),
+        "# Header\n[Desktop Entry]\n# Name follows\nName=New Name\nName[de]=Alt\nName[fr]=Nouveau\nExec=app\nComment=Line\\nbreak\n\n[Desktop Action new]\nName=New\n"
+    );
+    assert_eq!(document.keys("Desktop Entry"), vec!["Name", "Name[de]", "Name[fr]", "Exec", "Comment"]);
+}
+
+#[test]
+fn test_new_group_and_crlf() {
+    let mut document = DesktopFile::parse("[Desktop Entry]\r\nName=App\r\n");
+    document.set("Desktop Action quit", "Name", "Quit");
+
+    assert_eq!(document.to_string(), "[Desktop Entry]\r\nName=App\r\n\r\n[Desktop Action quit]\r\nName=Quit\r\n");
+    assert_eq!(document.groups(), vec!["Desktop Entry", "Desktop Action quit"]);
+
+    let mut empty = DesktopFile::parse("");
+    empty.set("Desktop Entry", "Type", "Application");
+    assert_eq!(empty.to_string(), "[Desktop Entry]\nType=Application\n");
+}
+
+#[test]
+fn test_escaping() {
+    assert_eq!(escape_value(" leading\ttab\\"), "\\sleading\\ttab\\\\");
+    assert_eq!(escape_value("trailing  "), "trailing \\s");
+    assert_eq!(escape_value(" "), "\\s");
+    assert_eq!(escape_list(&["a;b", "c"]), r"a\;b;c;");
+}
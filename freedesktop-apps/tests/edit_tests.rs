@@ -0,0 +1,103 @@
+mod common;
+
+use common::xdg_root;
+use freedesktop_apps::{user_override_path, ApplicationEntry, EditError, FindError};
+use std::fs;
+
+const CALCULATOR: &str = "# Shipped by the distribution\n[Desktop Entry]\nType=Application\nName=Calculator\nName[de]=Rechner\nExec=calc\nCategories=Utility;\n";
+
+fn setup() {
+    xdg_root(|dir| {
+        dir.write("system/applications/calc.desktop", CALCULATOR);
+        dir.write("system/applications/editor.desktop", &CALCULATOR.replace("calc", "editor"));
+        dir.write("system/applications/viewer.desktop", &CALCULATOR.replace("calc", "viewer"));
+        dir.write("system/applications/notes.desktop", &CALCULATOR.replace("calc", "notes"));
+
+        vec![("XDG_DATA_HOME", "home"), ("XDG_DATA_DIRS", "system")]
+    });
+}
+
+#[test]
+fn test_edits_update_entry() {
+    setup();
+
+    let mut entry = ApplicationEntry::from_id("calc").unwrap();
+    entry.set_string("Name", "My Calculator").unwrap();
+    entry.set_localized("Name", "fr", "Calculatrice").unwrap();
+    entry.set_list("Categories", &["Utility", "Math"]).unwrap();
+    entry.set_string("Comment", " Adds numbers").unwrap();
+    entry.remove_key("Name[de]").unwrap();
+
+    assert_eq!(entry.name().as_deref(), Some("My Calculator"));
+    assert_eq!(entry.get_localized_string("Name", Some("fr")).as_deref(), Some("Calculatrice"));
+    assert_eq!(entry.get_localized_string("Name", Some("de")).as_deref(), Some("My Calculator"));
+    assert_eq!(entry.categories(), Some(vec!["Utility".to_string(), "Math".to_string()]));
+    assert_eq!(entry.comment().as_deref(), Some(" Adds numbers"));
+
+    // Nothing is written until save()
+    assert!(!user_override_path("calc").exists());
+}
+
+#[test]
+fn test_invalid_keys() {
+    setup();
+
+    let mut entry = ApplicationEntry::from_id("calc").unwrap();
+    assert!(matches!(entry.set_string("Bad Key", "x"), Err(EditError::InvalidKey(_))));
+    assert!(matches!(entry.set_localized("Name", "d e", "x"), Err(EditError::InvalidKey(_))));
+}
+
+#[test]
+fn test_save_overrides_and_restore() {
+    setup();
+
+    let mut entry = ApplicationEntry::from_id("editor.desktop").unwrap();
+    entry.set_string("Icon", "my-editor").unwrap();
+    let path = entry.save().unwrap();
+
+    assert_eq!(path, user_override_path("editor"));
+    assert_eq!(entry.path(), path);
+
+    // The override keeps the original comments and order
+    let written = fs::read_to_string(&path).unwrap();
+    assert_eq!(written, CALCULATOR.replace("calc", "editor") + "Icon=my-editor\n");
+
+    let reloaded = ApplicationEntry::from_id("editor").unwrap();
+    assert_eq!(reloaded.icon().as_deref(), Some("my-editor"));
+
+    assert!(reloaded.restore_default().unwrap());
+    assert!(!path.exists());
+    assert_eq!(ApplicationEntry::from_id("editor").unwrap().icon(), None);
+}
+
+#[test]
+fn test_hide_and_unhide() {
+    setup();
+
+    let mut entry = ApplicationEntry::from_id("viewer").unwrap();
+    entry.set_hidden(true).unwrap();
+    entry.save().unwrap();
+
+    assert!(matches!(ApplicationEntry::from_id("viewer"), Err(FindError::NotFound(_))));
+    assert!(!ApplicationEntry::all().iter().any(|e| e.id().as_deref() == Some("viewer")));
+
+    assert!(ApplicationEntry::remove_override("viewer").unwrap());
+    assert!(!ApplicationEntry::remove_override("viewer").unwrap());
+    assert!(ApplicationEntry::from_id("viewer").is_ok());
+}
+
+#[test]
+fn test_spaces_survive_save() {
+    setup();
+
+    let mut entry = ApplicationEntry::from_id("notes").unwrap();
+    entry.set_string("Comment", " Takes notes  ").unwrap();
+    entry.set_localized("Comment", "de", "Notizen ").unwrap();
+    let path = entry.save().unwrap();
+
+    let reloaded = ApplicationEntry::from_path(&path).unwrap();
+    assert_eq!(reloaded.comment().as_deref(), Some(" Takes notes  "));
+    assert_eq!(reloaded.get_localized_string("Comment", Some("de")).as_deref(), Some("Notizen "));
+
+    assert!(ApplicationEntry::remove_override("notes").unwrap());
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{ApplicationEntry, Email, ExecuteError, LaunchContext, MailClient};
+use std::time::Duration;
+
+fn setup() {
+    xdg_root(|dir| {
+        dir.write(
+            "data/applications/mail.desktop",
+            "[Desktop Entry]\nType=Application\nName=Mail\nExec=true %u\n",
+        );
+        dir.write("config/mimeapps.list", "[Default Applications]\nx-scheme-handler/mailto=mail.desktop\n");
+
+        vec![
+            ("XDG_CONFIG_HOME", "config"),
+            ("XDG_CONFIG_DIRS", "etc"),
+            ("XDG_DATA_HOME", "data"),
+            ("XDG_DATA_DIRS", "system"),
+        ]
+    });
+}
+
+fn mail_handler(file_name: &str, exec: &str) -> ApplicationEntry {
+    let dir = TestDir::new("mail_handler");
+    let path = dir.write(
+        file_name,
+        &format!("[Desktop Entry]\nType=Application\nName=Mail\nExec={}\n", exec),
+    );
+    ApplicationEntry::try_from_path(path).unwrap()
+}
+
+#[test]
+fn test_mailto_uri_encoding() {
+    let email = Email::new()
+        .to("bugs@example.com")
+        .to("dev+list@example.com")
+        .cc("cc@example.com")
+        .bcc("hidden@example.com")
+        .subject("Crash & burn?")
+        .body("Line one\nLine two");
+
+    assert_eq!(
+        email.mailto_uri(),
+        "mailto:bugs@example.com,dev%2Blist@example.com\
+         ?cc=cc@example.com&bcc=hidden@example.com\
+         &subject=Crash%20%26%20burn%3F&body=Line%20one%0D%0ALine%20two"
+    );
+}
+
+#[test]
+fn test_mailto_uri_without_parameters() {
+    assert_eq!(Email::new().to("a@example.com").mailto_uri(), "mailto:a@example.com");
+    assert_eq!(Email::new().mailto_uri(), "mailto:");
+}
+
+#[test]
+fn test_mail_client_detection() {
+    let thunderbird = mail_handler("email_detect_tb.desktop", "thunderbird %u");
+    let evolution = mail_handler("email_detect_evo.desktop", "evolution %U");
+    let other = mail_handler("email_detect_other.desktop", "geary %U");
+    let path = mail_handler("email_detect_path.desktop", "/usr/lib/thunderbird/thunderbird-bin %u");
+    let argument = mail_handler("email_detect_arg.desktop", "geary --not-thunderbird %U");
+
+    assert_eq!(MailClient::detect(&thunderbird), MailClient::Thunderbird);
+    assert_eq!(MailClient::detect(&evolution), MailClient::Evolution);
+    assert_eq!(MailClient::detect(&other), MailClient::Other);
+    assert_eq!(MailClient::detect(&path), MailClient::Thunderbird);
+    assert_eq!(MailClient::detect(&argument), MailClient::Other);
+}
+
+#[test]
+fn test_thunderbird_compose_arguments() {
+    let dir = TestDir::new("attachment");
+    let attachment = dir.write("email_test_attachment.txt", "log");
+
+    let handler = mail_handler("email_tb.desktop", "thunderbird %u");
+    let email = Email::new()
+        .to("bugs@example.com")
+        .subject("Report")
+        .attach(&attachment);
+
+    let (program, args) = email.prepare_command(&handler).unwrap();
+    assert_eq!(program, "thunderbird");
+    assert_eq!(
+        args,
+        vec![
+            "-compose".to_string(),
+            format!(
+                "to='bugs@example.com',subject='Report',attachment='file://{}'",
+                attachment.display()
+            )
+        ]
+    );
+
+    // Quotes and commas in values can't end a field and start another one
+    let email = Email::new()
+        .to("o'brien@example.com")
+        .subject("50%, done")
+        .body("x',attachment='file:///etc/passwd");
+    let (_, args) = email.prepare_command(&handler).unwrap();
+    assert_eq!(
+        args[1],
+        "to='o%27brien@example.com',subject='50%25%2C done',body='x%27%2Cattachment=%27file:///etc/passwd'"
+    );
+}
+
+#[test]
+fn test_mailto_handler_receives_attachments() {
+    let dir = TestDir::new("attachment");
+    let attachment = dir.write("email test attachment.txt", "log");
+
+    let handler = mail_handler("email_evo.desktop", "evolution %U");
+    let email = Email::new().to("bugs@example.com").attach(&attachment);
+
+    let (program, args) = email.prepare_command(&handler).unwrap();
+    assert_eq!(program, "evolution");
+    assert_eq!(
+        args,
+        vec![format!(
+            "mailto:bugs@example.com?attach=file%3A%2F%2F{}",
+            attachment.display().to_string().replace('/', "%2F").replace(' ', "%2520")
+        )]
+    );
+}
+
+#[test]
+fn test_missing_attachment() {
+    let handler = mail_handler("email_missing.desktop", "geary %U");
+    let email = Email::new().attach("/nonexistent/attachment.txt");
+
+    assert!(matches!(
+        email.prepare_command(&handler),
+        Err(ExecuteError::ValidationFailed(_))
+    ));
+}
+
+#[test]
+fn test_compose_with_context() {
+    setup();
+
+    let context = LaunchContext::new().grace_period(Duration::from_secs(5));
+    let launched = Email::new().to("bugs@example.com").compose_with_context(&context).unwrap();
+    assert_eq!(launched.len(), 1);
+    assert_eq!(launched[0].desktop_id(), Some("mail"));
+    assert!(launched[0].process_group().is_some());
+    assert!(launched[0].early_exit().unwrap().success());
+
+    let mut launched = Email::new().compose().unwrap();
+    assert!(launched[0].wait().unwrap().success());
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{Entry, ExecuteError, LaunchContext, LinkEntry, ParseError};
+use std::time::Duration;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn setup() {
+    xdg_root(|dir| {
+        dir.write(
+            "data/applications/browser.desktop",
+            "[Desktop Entry]\nType=Application\nName=Browser\nExec=true %u\n",
+        );
+        dir.write(
+            "data/applications/docs.desktop",
+            "[Desktop Entry]\nType=Link\nName=Docs\nURL=https://example.com/docs\n",
+        );
+        dir.write(
+            "data/applications/gopher.desktop",
+            "[Desktop Entry]\nType=Link\nName=Gopher\nURL=gopher://example.com\n",
+        );
+        dir.write(
+            "data/applications/editor.desktop",
+            "[Desktop Entry]\nType=Application\nName=Editor\nExec=true %f\nMimeType=text/plain;\n",
+        );
+        dir.write(
+            "data/applications/files.desktop",
+            "[Desktop Entry]\nType=Application\nName=Files\nExec=true %U\nMimeType=inode/directory;\n",
+        );
+        dir.write("system/mime/globs2", "# Generated\n50:text/plain:*.txt\n10:text/x-readme:readme*\n");
+        dir.write(
+            "config/mimeapps.list",
+            "[Default Applications]\nx-scheme-handler/https=browser.desktop\ntext/plain=editor.desktop\n",
+        );
+
+        vec![
+            ("XDG_CONFIG_HOME", "config"),
+            ("XDG_CONFIG_DIRS", "etc"),
+            ("XDG_DATA_HOME", "data"),
+            ("XDG_DATA_DIRS", "system"),
+        ]
+    });
+}
+
+#[test]
+fn test_entry_type_dispatch() {
+    let app = Entry::from_path(fixture_path("minimal_app.desktop")).unwrap();
+    assert!(app.as_application().is_some());
+
+    let link = Entry::from_path(fixture_path("link_entry.desktop")).unwrap();
+    assert!(matches!(link, Entry::Link(_)));
+    assert_eq!(link.as_link().unwrap().get_string("Name").as_deref(), Some("Test Website Link"));
+
+    let directory = Entry::from_path(fixture_path("directory_entry.directory")).unwrap();
+    assert_eq!(directory.as_directory().unwrap().icon().as_deref(), Some("folder"));
+}
+
+#[test]
+fn test_unknown_entry_type() {
+    let dir = TestDir::new("unknown_type");
+    let temp_file = dir.write("service.desktop", "[Desktop Entry]\nType=Service\nName=Service\n");
+
+    assert!(matches!(Entry::from_path(temp_file), Err(ParseError::InvalidFormat(_))));
+}
+
+#[test]
+fn test_link_url_and_scheme() {
+    let link = LinkEntry::from_path(fixture_path("link_entry.desktop")).unwrap();
+    assert_eq!(link.url().as_deref(), Some("https://example.com"));
+    assert_eq!(link.scheme().as_deref(), Some("https"));
+
+    let dir = TestDir::new("local_link");
+    let temp_file = dir.write("home.desktop", "[Desktop Entry]\nType=Link\nName=Home\nURL=/home/user\n");
+    let local = LinkEntry::from_path(temp_file).unwrap();
+    assert_eq!(local.scheme().as_deref(), Some("file"));
+}
+
+#[test]
+fn test_link_open_uses_scheme_handler() {
+    setup();
+
+    let Some(Entry::Link(docs)) = Entry::from_id("docs").ok() else {
+        panic!("docs.desktop should load as a link");
+    };
+    assert_eq!(docs.handler().and_then(|h| h.name()).as_deref(), Some("Browser"));
+    assert!(docs.open().is_ok());
+
+    let Some(Entry::Link(gopher)) = Entry::from_id("gopher").ok() else {
+        panic!("gopher.desktop should load as a link");
+    };
+    assert!(matches!(gopher.open(), Err(ExecuteError::NoHandler(_))));
+}
+
+#[test]
+fn test_file_link_uses_mime_type_handler() {
+    setup();
+
+    let dir = TestDir::new("file_link");
+    let notes = dir.write("My Notes.TXT", "notes");
+    let link = |url: &str| {
+        let path = dir.write("link.desktop", &format!("[Desktop Entry]\nType=Link\nName=Notes\nURL={}\n", url));
+        LinkEntry::from_path(path).unwrap()
+    };
+
+    let notes_url = format!("file://{}", notes.display().to_string().replace(' ', "%20"));
+    assert_eq!(link(&notes_url).file_path(), Some(notes.clone()));
+    assert_eq!(freedesktop_apps::mime_type_for_path(&notes).as_deref(), Some("text/plain"));
+    assert_eq!(link(&notes_url).handler().and_then(|h| h.name()).as_deref(), Some("Editor"));
+
+    let mut launched = link(&notes_url).open().unwrap();
+    assert_eq!(launched.len(), 1);
+    assert!(launched[0].wait().unwrap().success());
+
+    // The handler is launched as the context says
+    let context = LaunchContext::new().grace_period(Duration::from_secs(5));
+    let launched = link(&notes_url).open_with_context(&context).unwrap();
+    assert_eq!(launched[0].desktop_id(), Some("editor"));
+    assert!(launched[0].early_exit().unwrap().success());
+
+    // Directories and files of unknown types go to the file manager
+    let folder = link(&dir.path().display().to_string());
+    assert_eq!(folder.handler().and_then(|h| h.name()).as_deref(), Some("Files"));
+    let unknown = link(&format!("file://{}", dir.join("data.bin").display()));
+    assert_eq!(unknown.handler().and_then(|h| h.name()).as_deref(), Some("Files"));
+}
+
+#[test]
+fn test_entry_name_is_localized() {
+    let app = Entry::from_path(fixture_path("complete_app.desktop")).unwrap();
+    let locale = freedesktop_core::info::Info::current_locale();
+
+    assert_eq!(
+        app.name(),
+        app.as_application().unwrap().get_localized_string("Name", locale.as_deref())
+    );
+}
+
+#[test]
+fn test_all_entries() {
+    setup();
+
+    let entries = Entry::all();
+    let links = entries.iter().filter(|e| e.as_link().is_some()).count();
+    let apps = entries.iter().filter(|e| e.as_application().is_some()).count();
+    assert_eq!((links, apps), (2, 3));
+}
@@ -0,0 +1,97 @@
+use freedesktop_apps::{ApplicationEntry, ExecCommand, ExecError};
+use freedesktop_core::info::Info;
+use std::path::Path;
+
+fn app(exec: &str) -> ApplicationEntry {
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName=Viewer\nName[de]=Betrachter\nIcon=viewer\nExec={}\n",
+        exec
+    );
+    ApplicationEntry::from_str(&content, Some(Path::new("/usr/share/applications/viewer.desktop"))).unwrap()
+}
+
+/// %c is the name translated for the locale the tests run in
+fn translated_name() -> String {
+    app("viewer").get_localized_string("Name", Info::current_locale().as_deref()).unwrap()
+}
+
+/// Tokenize an Exec value that has already been unescaped at string level
+fn argv(exec: &str, files: &[&str], urls: &[&str]) -> Vec<String> {
+    ExecCommand::parse(exec).unwrap().argv(&app("viewer"), files, urls)
+}
+
+#[test]
+fn test_tokenize() {
+    assert_eq!(argv("viewer  --flag\targ", &[], &[]), vec!["viewer", "--flag", "arg"]);
+    assert_eq!(
+        argv(r#""/opt/My App/viewer" "a \"quoted\" \$HOME \`x\` \\" 'single' esc\ aped"#, &[], &[]),
+        vec!["/opt/My App/viewer", r#"a "quoted" $HOME `x` \"#, "single", "esc aped"]
+    );
+    assert_eq!(argv(r#"viewer "" --progress=100%%"#, &[], &[]), vec!["viewer", "", "--progress=100%"]);
+
+    // The string level escape \\ leaves a single backslash for the Exec level
+    let entry = ApplicationEntry::from_str(
+        "[Desktop Entry]\nType=Application\nName=A\nExec=sh -c \"echo \\\\$PATH\"\n",
+        None,
+    )
+    .unwrap();
+    assert_eq!(entry.exec_command().unwrap().argv(&entry, &[], &[]), vec!["sh", "-c", "echo $PATH"]);
+}
+
+#[test]
+fn test_field_codes() {
+    let files = ["/tmp/it's a \"file\" $HOME.txt", "/tmp/second.txt"];
+
+    assert_eq!(argv("viewer %F", &files, &[]), vec!["viewer", files[0], files[1]]);
+    assert_eq!(argv("viewer --file=%f", &files, &[]), vec!["viewer", &format!("--file={}", files[0])]);
+    assert_eq!(argv("viewer %f", &[], &[]), vec!["viewer"]);
+    assert_eq!(argv("viewer %U", &[], &["https://example.com/a b"]), vec!["viewer", "https://example.com/a b"]);
+    assert_eq!(
+        argv("viewer %i %c %k %d %m", &[], &[]),
+        vec!["viewer", "--icon", "viewer", &translated_name(), "/usr/share/applications/viewer.desktop"]
+    );
+
+    let command = ExecCommand::parse("viewer --name=%c %i %U").unwrap();
+    assert_eq!(command.field_codes(), vec!['c', 'i', 'U']);
+    assert_eq!(argv("viewer --title=%c", &[], &[]), vec!["viewer".to_string(), format!("--title={}", translated_name())]);
+    assert_eq!(app("viewer").get_localized_string("Name", Some("de_DE")).as_deref(), Some("Betrachter"));
+}
+
+#[test]
+fn test_instances() {
+    let entry = app("viewer");
+    let files = ["/tmp/a.txt", "/tmp/b.txt"];
+    let urls = ["https://example.com", "https://example.org"];
+    let instances = |exec: &str, files: &[&str], urls: &[&str]| {
+        ExecCommand::parse(exec).unwrap().instances(&entry, files, urls)
+    };
+
+    assert_eq!(instances("viewer %f", &files, &[]), vec![vec!["viewer", "/tmp/a.txt"], vec!["viewer", "/tmp/b.txt"]]);
+    assert_eq!(instances("viewer %F", &files, &[]), vec![vec!["viewer", "/tmp/a.txt", "/tmp/b.txt"]]);
+    assert_eq!(
+        instances("viewer --url %u", &[], &urls),
+        vec![vec!["viewer", "--url", urls[0]], vec!["viewer", "--url", urls[1]]]
+    );
+    assert_eq!(instances("viewer %f", &[], &[]), vec![vec!["viewer"]]);
+    assert_eq!(instances("viewer", &files, &[]), vec![vec!["viewer"]]);
+
+    let entry = app("viewer %f");
+    let commands = entry.prepare_command(&files, &[]).unwrap();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[1], ("viewer".to_string(), vec!["/tmp/b.txt".to_string()]));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(ExecCommand::parse("  "), Err(ExecError::Empty));
+    assert_eq!(ExecCommand::parse("%f"), Err(ExecError::Empty));
+    assert_eq!(ExecCommand::parse("viewer \"open"), Err(ExecError::UnterminatedQuote));
+    assert_eq!(ExecCommand::parse("viewer > log"), Err(ExecError::ReservedCharacter('>')));
+    assert_eq!(ExecCommand::parse("viewer \"\\n\""), Err(ExecError::InvalidEscape('n')));
+    assert_eq!(ExecCommand::parse("viewer \"%f\""), Err(ExecError::FieldCodeInQuotes("%f".to_string())));
+    assert_eq!(ExecCommand::parse("viewer %x"), Err(ExecError::InvalidFieldCode("%x".to_string())));
+    assert_eq!(ExecCommand::parse("viewer --files=%F"), Err(ExecError::NotStandalone("%F".to_string())));
+
+    let entry = app("viewer $HOME");
+    assert!(entry.prepare_command(&[], &[]).is_err());
+}
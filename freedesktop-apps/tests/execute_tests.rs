@@ -66,7 +66,7 @@ Exec=echo "arg with spaces" 'single quotes' normal_arg
     assert!(entry.exec().is_some());
     
     // Test that complex command lines don't cause validation to fail
-    let result = entry.prepare_command(&[], &[]);
+    let result = entry.prepare_command(&[], &[]).map(|mut commands| commands.remove(0));
     match result {
         Ok((program, args)) => {
             assert_eq!(program, "echo");
@@ -105,7 +105,7 @@ fn test_try_exec_with_valid_program() {
     let entry = ApplicationEntry::try_from_path(temp_file).unwrap();
     
     // This should pass validation since 'echo' should be in PATH
-    match entry.prepare_command(&[], &[]) {
+    match entry.prepare_command(&[], &[]).map(|mut commands| commands.remove(0)) {
         Ok((program, args)) => {
             assert_eq!(program, "echo");
             assert_eq!(args, vec!["test"]);
@@ -128,7 +128,7 @@ fn test_terminal_application() {
     assert!(entry.terminal());
     
     // Test that it tries to find a terminal (may fail if no terminal available)
-    let result = entry.prepare_command(&[], &[]);
+    let result = entry.prepare_command(&[], &[]).map(|mut commands| commands.remove(0));
     match result {
         Ok((program, args)) => {
             // Should have wrapped with a terminal - check for common terminal names
@@ -164,7 +164,7 @@ fn test_execute_with_files() {
     
     // Test with files
     let files = vec!["/tmp/test1.txt", "/tmp/test2.txt"];
-    let result = entry.prepare_command(&files, &[]);
+    let result = entry.prepare_command(&files, &[]).map(|mut commands| commands.remove(0));
     
     match result {
         Ok((program, args)) => {
@@ -191,7 +191,7 @@ fn test_execute_with_urls() {
     
     // Test with URLs
     let urls = vec!["https://example.com", "https://test.org"];
-    let result = entry.prepare_command(&[], &urls);
+    let result = entry.prepare_command(&[], &urls).map(|mut commands| commands.remove(0));
     
     match result {
         Ok((program, args)) => {
@@ -218,7 +218,7 @@ fn test_working_directory() {
     assert_eq!(entry.path_dir(), Some("/tmp".to_string()));
     
     // Test preparation works (working directory is handled in actual execution)
-    let result = entry.prepare_command(&[], &[]);
+    let result = entry.prepare_command(&[], &[]).map(|mut commands| commands.remove(0));
     match result {
         Ok((program, args)) => {
             assert_eq!(program, "pwd");
@@ -247,7 +247,7 @@ Exec=echo %f
     let dangerous_files = vec!["file with spaces", "file'with'quotes", "file;with;semicolons"];
     
     for file in dangerous_files {
-        let result = entry.prepare_command(&[file], &[]);
+        let result = entry.prepare_command(&[file], &[]).map(|mut commands| commands.remove(0));
         match result {
             Ok((program, args)) => {
                 assert_eq!(program, "echo");
@@ -0,0 +1,88 @@
+use freedesktop_apps::{ApplicationEntry, Entry};
+use std::collections::BTreeMap;
+
+const FLATPAK_APP: &str = r"[Desktop Entry]
+Type=Application
+Name=Editor
+Name[de]=Bearbeiter
+Name[fr]=Éditeur
+Keywords=text;edit;
+Keywords[de]=Text;Bearbeiten;
+Exec=/usr/bin/flatpak run org.example.Editor
+X-GNOME-UsesNotifications=true
+X-Steam-AppId=440
+X-Flatpak=org.example.Editor
+
+[Desktop Action new-window]
+Name=New Window
+Exec=/usr/bin/flatpak run org.example.Editor --new-window
+
+[X-Flatpak]
+Runtime=org.gnome.Platform/x86_64/46
+Permissions=network;wayland;ipc\;shared;
+Summary=A text\seditor
+Summary[de]=Ein Texteditor
+";
+
+#[test]
+fn test_groups() {
+    let entry = ApplicationEntry::from_str(FLATPAK_APP, None).unwrap();
+
+    assert_eq!(entry.groups(), vec!["Desktop Entry", "Desktop Action new-window", "X-Flatpak"]);
+    assert!(entry.group("X-Missing").is_none());
+
+    let main = entry.group("Desktop Entry").unwrap();
+    assert_eq!(
+        main.keys(),
+        vec!["Type", "Name", "Keywords", "Exec", "X-GNOME-UsesNotifications", "X-Steam-AppId", "X-Flatpak"]
+    );
+    assert_eq!(main.get_bool("X-GNOME-UsesNotifications"), Some(true));
+    assert_eq!(main.get_numeric("X-Steam-AppId"), Some(440.0));
+    assert_eq!(main.get_string("X-Flatpak"), Some("org.example.Editor".to_string()));
+
+    let action = entry.group("Desktop Action new-window").unwrap();
+    assert_eq!(action.get_string("Name"), Some("New Window".to_string()));
+}
+
+#[test]
+fn test_vendor_group() {
+    let entry = ApplicationEntry::from_str(FLATPAK_APP, None).unwrap();
+    let flatpak = entry.group("X-Flatpak").unwrap();
+
+    assert_eq!(flatpak.name(), "X-Flatpak");
+    assert_eq!(flatpak.keys(), vec!["Runtime", "Permissions", "Summary"]);
+    assert!(flatpak.has_key("Runtime"));
+    assert_eq!(flatpak.get_string("Runtime"), Some("org.gnome.Platform/x86_64/46".to_string()));
+    assert_eq!(
+        flatpak.get_vec("Permissions"),
+        Some(vec!["network".to_string(), "wayland".to_string(), "ipc;shared".to_string()])
+    );
+    assert_eq!(flatpak.get_raw("Summary"), Some("A text\\seditor"));
+    assert_eq!(flatpak.get_string("Summary"), Some("A text editor".to_string()));
+    assert_eq!(flatpak.get_localized_string("Summary", Some("de_AT")), Some("Ein Texteditor".to_string()));
+    assert_eq!(flatpak.get_raw("Summary[de]"), Some("Ein Texteditor"));
+}
+
+#[test]
+fn test_translations() {
+    let entry = ApplicationEntry::from_str(FLATPAK_APP, None).unwrap();
+    let main = entry.group("Desktop Entry").unwrap();
+
+    assert_eq!(
+        main.translations("Name"),
+        BTreeMap::from([
+            ("de".to_string(), "Bearbeiter".to_string()),
+            ("fr".to_string(), "Éditeur".to_string()),
+        ])
+    );
+    assert_eq!(
+        main.list_translations("Keywords"),
+        BTreeMap::from([("de".to_string(), vec!["Text".to_string(), "Bearbeiten".to_string()])])
+    );
+    assert!(main.translations("Exec").is_empty());
+
+    let link = Entry::from_path(format!("{}/tests/fixtures/link_entry.desktop", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let link = link.as_link().unwrap();
+    assert_eq!(link.groups(), vec!["Desktop Entry"]);
+    assert_eq!(link.group("Desktop Entry").unwrap().translations("Name").len(), 1);
+}
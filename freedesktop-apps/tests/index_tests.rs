@@ -0,0 +1,133 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{application_entry_paths, ApplicationEntry, DesktopFileIndex, Entry, FindError};
+
+/// home > local > system in XDG order
+fn setup() -> &'static TestDir {
+    xdg_root(|dir| {
+        dir.write_app("system", "editor.desktop", "System Editor", "");
+        dir.write_app("system", "kde4/foo.desktop", "Foo", "");
+        dir.write_app("system", "removed.desktop", "Removed", "");
+        dir.write_app("local", "editor.desktop", "Local Editor", "");
+        dir.write_app("home", "removed.desktop", "Removed", "Hidden=true\n");
+        dir.write_app("home", "org.gnome.Text-Editor.desktop", "Text Editor", "");
+        dir.write_app("system", "broken.desktop", "Broken", "");
+        dir.write("home/applications/broken.desktop", "Name=No group\n");
+
+        vec![("XDG_DATA_HOME", "home"), ("XDG_DATA_DIRS", "local:system")]
+    })
+}
+
+fn names(entries: &[ApplicationEntry]) -> Vec<String> {
+    entries.iter().map(|e| e.name().unwrap_or_default()).collect()
+}
+
+#[test]
+fn test_application_dirs_in_xdg_order() {
+    let dir = setup();
+
+    assert_eq!(
+        application_entry_paths(),
+        vec![
+            dir.join("home/applications"),
+            dir.join("local/applications"),
+            dir.join("system/applications"),
+        ]
+    );
+}
+
+#[test]
+fn test_index_ids_and_shadowing() {
+    let dir = setup();
+
+    let index = DesktopFileIndex::load();
+    let ids: Vec<&str> = index.ids().collect();
+    assert_eq!(
+        ids,
+        vec![
+            "broken.desktop",
+            "editor.desktop",
+            "kde4-foo.desktop",
+            "org.gnome.Text-Editor.desktop",
+            "removed.desktop"
+        ]
+    );
+
+    let editor = index.get("editor").unwrap();
+    assert_eq!(editor.path(), dir.join("local/applications/editor.desktop"));
+    assert_eq!(
+        editor.shadowed(),
+        [dir.join("system/applications/editor.desktop")]
+    );
+}
+
+#[test]
+fn test_all_uses_index() {
+    setup();
+
+    // One entry per ID, the masked one is gone
+    let mut all = ApplicationEntry::all();
+    all.sort_by_key(|e| e.name());
+    assert_eq!(names(&all), vec!["Foo", "Local Editor", "Text Editor"]);
+
+    let foo = all.iter().find(|e| e.name().as_deref() == Some("Foo")).unwrap();
+    assert_eq!(foo.id().as_deref(), Some("kde4-foo"));
+}
+
+#[test]
+fn test_from_id_uses_index() {
+    setup();
+
+    assert_eq!(ApplicationEntry::from_id("editor.desktop").unwrap().name().as_deref(), Some("Local Editor"));
+    assert_eq!(ApplicationEntry::from_id("kde4-foo.desktop").unwrap().name().as_deref(), Some("Foo"));
+    assert!(matches!(ApplicationEntry::from_id("removed.desktop"), Err(FindError::NotFound(_))));
+}
+
+#[test]
+fn test_from_id_does_not_guess_paths() {
+    setup();
+
+    // Dashes are part of the file name, not directory separators
+    let editor = ApplicationEntry::from_id("org.gnome.Text-Editor").unwrap();
+    assert_eq!(editor.name().as_deref(), Some("Text Editor"));
+    assert_eq!(editor.id().as_deref(), Some("org.gnome.Text-Editor"));
+
+    assert!(matches!(ApplicationEntry::from_id("kde4/foo.desktop"), Err(FindError::NotFound(_))));
+    assert!(matches!(ApplicationEntry::from_id("org.gnome.Text"), Err(FindError::NotFound(_))));
+}
+
+#[test]
+fn test_from_id_reports_invalid_entry() {
+    let dir = setup();
+
+    // The user's broken file wins over the valid system one
+    match ApplicationEntry::from_id("broken") {
+        Err(FindError::InvalidEntry(path, _)) => {
+            assert_eq!(path, dir.join("home/applications/broken.desktop"));
+        }
+        other => panic!("Expected InvalidEntry, got {:?}", other.map(|e| e.name())),
+    }
+}
+
+#[test]
+fn test_id_of_file_loaded_by_path() {
+    let dir = setup();
+
+    let entry = ApplicationEntry::try_from_path(dir.join("system/applications/kde4/foo.desktop")).unwrap();
+    assert_eq!(entry.id().as_deref(), Some("kde4-foo"));
+}
+
+#[test]
+fn test_find_only_returns_applications() {
+    let dir = TestDir::new("index_types");
+    dir.write_app("apps", "editor.desktop", "Editor", "");
+    dir.write("apps/applications/docs.desktop", "[Desktop Entry]\nType=Link\nName=Docs\nURL=https://example.com\n");
+
+    let index = DesktopFileIndex::from_dirs(&[dir.join("apps/applications")]);
+    assert_eq!(index.find("editor").unwrap().id().as_deref(), Some("editor"));
+    assert!(matches!(index.find("docs"), Err(FindError::NotFound(_))));
+
+    assert!(matches!(index.find_entry("docs"), Ok(Entry::Link(_))));
+    assert!(matches!(index.find_entry("editor"), Ok(Entry::Application(_))));
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{log_dir, ApplicationEntry, ExecuteError, LaunchContext};
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+fn setup() -> &'static TestDir {
+    // Inherited from whoever launched the tests
+    xdg_root(|_| {
+        vec![
+            ("XDG_STATE_HOME", "state"),
+            ("DESKTOP_STARTUP_ID", "stale-id"),
+            ("LAUNCH_TEST_REMOVED", "1"),
+        ]
+    })
+}
+
+fn app(name: &str, exec: &str, startup_notify: bool) -> (ApplicationEntry, String) {
+    let desktop_file = setup().write(
+        &format!("{}.desktop", name),
+        &format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nStartupNotify={}\n",
+            name, exec, startup_notify
+        ),
+    );
+
+    let entry = ApplicationEntry::try_from_path(&desktop_file).unwrap();
+    (entry, desktop_file.display().to_string())
+}
+
+/// Launch an entry that writes its environment to a file and read it back
+fn launched_env(name: &str, startup_notify: bool, context: &LaunchContext) -> (HashMap<String, String>, u32, String) {
+    let output = setup().join(&format!("{}.env", name));
+    let (entry, desktop_file) = app(name, &format!("sh -c \"env > {}\"", output.display()), startup_notify);
+
+    let mut launched = entry.execute_with_context(&[], &[], context).unwrap();
+    assert_eq!(launched.len(), 1);
+    assert!(launched[0].wait().unwrap().success());
+
+    let env = fs::read_to_string(&output)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    (env, launched[0].pid(), desktop_file)
+}
+
+#[test]
+fn test_launch_environment() {
+    let context = LaunchContext::new()
+        .wayland_display("wayland-nested")
+        .display(":9")
+        .env("LAUNCH_TEST_EXTRA", "extra value")
+        .remove_env("LAUNCH_TEST_REMOVED");
+    let (env, pid, desktop_file) = launched_env("environment", false, &context);
+
+    assert_eq!(env.get("WAYLAND_DISPLAY").map(String::as_str), Some("wayland-nested"));
+    assert_eq!(env.get("DISPLAY").map(String::as_str), Some(":9"));
+    assert_eq!(env.get("LAUNCH_TEST_EXTRA").map(String::as_str), Some("extra value"));
+    assert!(!env.contains_key("LAUNCH_TEST_REMOVED"));
+    assert!(env.contains_key("PATH"));
+
+    assert_eq!(env.get("GIO_LAUNCHED_DESKTOP_FILE"), Some(&desktop_file));
+    assert_eq!(env.get("GIO_LAUNCHED_DESKTOP_FILE_PID"), Some(&pid.to_string()));
+
+    // Without StartupNotify=true no token is passed on, not even an inherited one
+    assert!(!env.contains_key("DESKTOP_STARTUP_ID"));
+    assert!(!env.contains_key("XDG_ACTIVATION_TOKEN"));
+}
+
+#[test]
+fn test_startup_notification() {
+    let context = LaunchContext::new().timestamp(1234);
+    let (env, _, _) = launched_env("generated_id", true, &context);
+    let id = &env["DESKTOP_STARTUP_ID"];
+    assert!(id.contains("-generated_id-"), "{}", id);
+    assert!(id.ends_with("_TIME1234"), "{}", id);
+    assert_eq!(env.get("XDG_ACTIVATION_TOKEN"), Some(id));
+
+    let (other, _, _) = launched_env("generated_id", true, &context);
+    assert_ne!(other["DESKTOP_STARTUP_ID"], *id);
+
+    let context = LaunchContext::new().activation_token("compositor-token");
+    let (env, _, _) = launched_env("activation_token", true, &context);
+    assert_eq!(env.get("XDG_ACTIVATION_TOKEN").map(String::as_str), Some("compositor-token"));
+    assert_eq!(env.get("DESKTOP_STARTUP_ID").map(String::as_str), Some("compositor-token"));
+}
+
+#[test]
+fn test_launched_app() {
+    let (entry, _) = app("logging", "sh -c \"echo out; echo err >&2\"", false);
+    let context = LaunchContext::new().grace_period(Duration::from_secs(5));
+
+    let mut launched = entry.execute_with_context(&[], &[], &context).unwrap();
+    let app = &mut launched[0];
+    assert_eq!(app.desktop_id(), Some("logging"));
+    assert_eq!(app.process_group(), Some(app.pid()));
+
+    // The grace period ends early once the process is gone
+    assert!(app.early_exit().unwrap().success());
+    assert!(app.launch_error().is_none());
+    assert_eq!(app.log_path(), Some(log_dir().join("logging.log").as_path()));
+    assert_eq!(fs::read_to_string(app.log_path().unwrap()).unwrap(), "out\nerr\n");
+}
+
+#[test]
+fn test_immediate_failure() {
+    let (entry, _) = app("failing", "sh -c \"echo missing library >&2; exit 3\"", false);
+    let context = LaunchContext::new().grace_period(Duration::from_secs(5));
+
+    // The handle is returned along with how the process exited
+    let mut launched = entry.execute_with_context(&[], &[], &context).unwrap();
+    assert_eq!(launched[0].early_exit().unwrap().code(), Some(3));
+    match launched[0].launch_error() {
+        Some(ExecuteError::LaunchFailed(message)) => {
+            assert!(message.contains("failing"), "{}", message);
+            assert!(message.contains("3"), "{}", message);
+        }
+        other => panic!("Expected LaunchFailed, got {:?}", other),
+    }
+    assert_eq!(launched[0].wait().unwrap().code(), Some(3));
+    let log = fs::read_to_string(log_dir().join("failing.log")).unwrap();
+    assert!(log.contains("missing library"));
+
+    // Without a grace period the launch isn't watched
+    let mut launched = entry.execute_with_context(&[], &[], &LaunchContext::new()).unwrap();
+    assert_eq!(launched[0].early_exit(), None);
+    assert_eq!(launched[0].wait().unwrap().code(), Some(3));
+
+    // Still running at the end of the grace period is a successful launch
+    let (entry, _) = app("running", "sleep 5", false);
+    let context = LaunchContext::new().grace_period(Duration::from_millis(50));
+    let launched = entry.execute_with_context(&[], &[], &context).unwrap();
+    assert_eq!(launched[0].early_exit(), None);
+    let mut child = launched.into_iter().next().unwrap().into_child();
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_partial_launch() {
+    // One instance per file; the second can't be started because its argument holds a NUL byte
+    let (entry, _) = app("partial", "true %f", false);
+    match entry.execute_with_context(&["first", "sec\0ond"], &[], &LaunchContext::new()) {
+        Err(ExecuteError::PartialLaunch(mut launched, err)) => {
+            assert_eq!(launched.len(), 1);
+            assert!(matches!(*err, ExecuteError::IoError(_)), "{:?}", err);
+            assert!(launched[0].wait().unwrap().success());
+        }
+        other => panic!("Expected PartialLaunch, got {:?}", other),
+    }
+
+    // When the first instance fails there is nothing to hand back
+    match entry.execute_with_context(&["fir\0st", "second"], &[], &LaunchContext::new()) {
+        Err(ExecuteError::IoError(_)) => {}
+        other => panic!("Expected IoError, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_program_found_in_launch_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Only the launched environment has this program in its PATH
+    let bin = TestDir::new("bin");
+    let output = bin.join("probe.out");
+    let program = bin.write("launch-path-probe", &format!("#!/bin/sh\necho \"$0\" > {}\n", output.display()));
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let (entry, _) = app("path_probe", "launch-path-probe", false);
+    assert!(entry.execute_with_context(&[], &[], &LaunchContext::new()).is_err());
+
+    let path = format!("{}:{}", bin.path().display(), std::env::var("PATH").unwrap_or_default());
+    let context = LaunchContext::new().env("PATH", path);
+    let mut launched = entry.execute_with_context(&[], &[], &context).unwrap();
+    assert!(launched[0].wait().unwrap().success());
+    assert_eq!(fs::read_to_string(&output).unwrap().trim(), program.display().to_string());
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::TestDir;
+use freedesktop_apps::{DesktopFileIndex, FilterReason, FindError, ParseError, ParseOptions};
+
+fn setup() -> (TestDir, DesktopFileIndex) {
+    let dir = TestDir::new("load");
+
+    dir.write_app("system", "shown.desktop", "shown.desktop", "");
+    dir.write_app("system", "removed.desktop", "removed.desktop", "");
+    dir.write_app("system", "nodisplay.desktop", "nodisplay.desktop", "NoDisplay=true\n");
+    dir.write_app("system", "kde-only.desktop", "kde-only.desktop", "OnlyShowIn=KDE;\n");
+    dir.write_app("system", "not-gnome.desktop", "not-gnome.desktop", "NotShowIn=GNOME;\n");
+    dir.write_app("system", "missing.desktop", "missing.desktop", "TryExec=/nonexistent/program\n");
+    dir.write_app("system", "kde4/lenient.desktop", "kde4/lenient.desktop", "Bad Key=1\n");
+    dir.write_app("home", "removed.desktop", "removed.desktop", "Hidden=true\n");
+    dir.write("system/applications/broken.desktop", "Name=No group\n");
+    dir.write(
+        "system/applications/link.desktop",
+        "[Desktop Entry]\nType=Link\nName=Link\nURL=https://example.com\n",
+    );
+
+    let index = DesktopFileIndex::from_dirs(&[dir.join("home/applications"), dir.join("system/applications")]);
+    (dir, index)
+}
+
+#[test]
+fn test_load_report() {
+    let (dir, index) = setup();
+    let report = index.load_applications(&["GNOME"]);
+
+    let shown: Vec<String> = report.entries.iter().filter_map(|e| e.id()).collect();
+    assert_eq!(shown, vec!["kde4-lenient", "shown"]);
+    assert_eq!(report.entries[0].warnings().len(), 1);
+
+    let filtered: Vec<(String, FilterReason)> = report
+        .filtered
+        .iter()
+        .map(|(entry, reason)| (entry.id().unwrap(), *reason))
+        .collect();
+    assert_eq!(
+        filtered,
+        vec![
+            ("kde-only".to_string(), FilterReason::OnlyShowIn),
+            ("missing".to_string(), FilterReason::TryExec),
+            ("nodisplay".to_string(), FilterReason::NoDisplay),
+            ("not-gnome".to_string(), FilterReason::NotShowIn),
+            ("removed".to_string(), FilterReason::Hidden),
+        ]
+    );
+
+    assert_eq!(report.failures.len(), 1);
+    let (path, error) = &report.failures[0];
+    assert!(path.ends_with("system/applications/broken.desktop"));
+    assert!(matches!(error, ParseError::MissingRequiredKey(_)));
+
+    // The user's Hidden=true file masks the system one
+    assert_eq!(
+        report.shadowed,
+        vec![(
+            dir.join("system/applications/removed.desktop"),
+            dir.join("home/applications/removed.desktop"),
+        )]
+    );
+}
+
+#[test]
+fn test_load_report_desktops() {
+    let (_dir, index) = setup();
+    let report = index.load_applications(&["KDE"]);
+
+    let shown: Vec<String> = report.entries.iter().filter_map(|e| e.id()).collect();
+    assert_eq!(shown, vec!["kde-only", "kde4-lenient", "not-gnome", "shown"]);
+    assert_eq!(FilterReason::TryExec.to_string(), "TryExec program is not installed");
+}
+
+#[test]
+fn test_index_parse_options() {
+    let (_dir, index) = setup();
+
+    let lenient = index.find("kde4-lenient").unwrap();
+    assert_eq!(lenient.warnings().len(), 1);
+    assert!(matches!(
+        index.find_with_options("kde4-lenient", &ParseOptions::strict()),
+        Err(FindError::InvalidEntry(_, _))
+    ));
+
+    let ids = |apps: Vec<freedesktop_apps::ApplicationEntry>| apps.iter().filter_map(|e| e.id()).collect::<Vec<_>>();
+    assert!(ids(index.applications()).contains(&"kde4-lenient".to_string()));
+    assert!(!ids(index.applications_with_options(&ParseOptions::strict())).contains(&"kde4-lenient".to_string()));
+    assert_eq!(index.entries_with_options(&ParseOptions::strict()).len(), index.entries().len() - 1);
+}
@@ -0,0 +1,255 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{menu_file_path, LayoutNode, Menu, MenuEditor, MenuError, MenuItem};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/menus/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn entry_names(menu: &Menu) -> Vec<String> {
+    menu.entries()
+        .iter()
+        .map(|e| e.name().unwrap_or_default())
+        .collect()
+}
+
+fn submenu_names(menu: &Menu) -> Vec<&str> {
+    menu.submenus().iter().map(|m| m.name()).collect()
+}
+
+fn layout_items(menu: &Menu) -> Vec<String> {
+    menu.layout()
+        .iter()
+        .map(|item| match item {
+            MenuItem::Entry { id, alias: None, .. } => format!("entry:{}", id),
+            MenuItem::Entry { id, alias: Some(menu), .. } => format!("alias:{}:{}", menu.name(), id),
+            MenuItem::Submenu(menu) => format!("menu:{}", menu.name()),
+            MenuItem::Header(menu) => format!("header:{}", menu.name()),
+            MenuItem::Separator => "---".to_string(),
+        })
+        .collect()
+}
+
+/// Tests that look up menus through the XDG directories
+/// share one fake config tree for the whole process.
+fn setup() -> &'static TestDir {
+    xdg_root(|dir| {
+        let apps = dir.write(
+            "apps/editor.desktop",
+            "[Desktop Entry]\nType=Application\nName=Editor\nExec=editor\nCategories=TextEditor;\n",
+        );
+
+        dir.write(
+            "etc/xdg/menus/test-applications.menu",
+            &format!(
+                "<Menu>\n  <Name>Applications</Name>\n  <AppDir>{}</AppDir>\n  <DefaultMergeDirs/>\n</Menu>\n",
+                apps.parent().unwrap().display()
+            ),
+        );
+
+        // The system merge dir adds the menu, the user merge dir deletes it again
+        dir.write(
+            "etc/xdg/menus/applications-merged/editors.menu",
+            "<Menu>\n  <Name>Applications</Name>\n  <Menu>\n    <Name>Editors</Name>\n    <Include><Category>TextEditor</Category></Include>\n  </Menu>\n  <Menu>\n    <Name>Hidden</Name>\n    <Include><All/></Include>\n  </Menu>\n</Menu>\n",
+        );
+        dir.write(
+            "config/menus/applications-merged/hide.menu",
+            "<Menu>\n  <Name>Applications</Name>\n  <Menu>\n    <Name>Hidden</Name>\n    <Deleted/>\n  </Menu>\n</Menu>\n",
+        );
+
+        vec![
+            ("XDG_CONFIG_HOME", "config"),
+            ("XDG_CONFIG_DIRS", "etc/xdg"),
+            ("XDG_MENU_PREFIX", "test-"),
+        ]
+    })
+}
+
+#[test]
+fn test_menu_structure() {
+    let menu = Menu::from_path(fixture_path("applications.menu")).expect("Failed to load menu");
+
+    assert_eq!(menu.name(), "Applications");
+    assert!(menu.entries().is_empty());
+
+    // Graphics is deleted, Office has NoDisplay=true in
+    // its .directory file and Empty has no entries
+    assert_eq!(submenu_names(&menu), vec!["Development", "Games", "Other"]);
+}
+
+#[test]
+fn test_category_and_filename_rules() {
+    let menu = Menu::from_path(fixture_path("applications.menu")).unwrap();
+
+    // extra-game.desktop comes from <MergeFile> by filename,
+    // hidden-game.desktop has NoDisplay=true
+    let games = menu.submenu("Games").unwrap();
+    assert_eq!(entry_names(games), vec!["Chess", "Extra Game", "Tetris"]);
+    assert_eq!(
+        games.entry_ids(),
+        ["chess.desktop", "extra-game.desktop", "tetris.desktop"]
+    );
+
+    let arcade = menu.submenu("Games/Arcade").unwrap();
+    assert_eq!(entry_names(arcade), vec!["Tetris"]);
+}
+
+#[test]
+fn test_exclude_rule() {
+    let menu = Menu::from_path(fixture_path("applications.menu")).unwrap();
+
+    let development = menu.submenu("Development").unwrap();
+    assert_eq!(entry_names(development), vec!["IDE"]);
+}
+
+#[test]
+fn test_merge_dir_and_move() {
+    let menu = Menu::from_path(fixture_path("applications.menu")).unwrap();
+
+    // Tools comes from <MergeDir> and is moved below Development.
+    // Entries in subdirectories get IDs like vendor-tool.desktop
+    assert!(menu.submenu("Tools").is_none());
+    let tools = menu.submenu("Development/Tools").unwrap();
+    assert_eq!(entry_names(tools), vec!["Core", "Vendor Tool"]);
+    assert_eq!(tools.entry_ids(), ["core.desktop", "vendor-tool.desktop"]);
+}
+
+#[test]
+fn test_only_unallocated() {
+    let menu = Menu::from_path(fixture_path("applications.menu")).unwrap();
+
+    // Everything except Core that no other menu claimed.
+    // GIMP only belongs to the deleted Graphics menu.
+    let other = menu.submenu("Other").unwrap();
+    assert_eq!(entry_names(other), vec!["GIMP", "Misc", "Old IDE"]);
+}
+
+#[test]
+fn test_directory_lookup() {
+    let menu = Menu::from_path(fixture_path("applications.menu")).unwrap();
+
+    let games = menu.submenu("Games").unwrap();
+    assert_eq!(
+        games.directory_path().map(|p| p.to_path_buf()),
+        Some(fixture_path("directories/games.directory").into())
+    );
+    assert_eq!(games.display_name(), "Games & Fun");
+    assert_eq!(games.icon().as_deref(), Some("applications-games"));
+
+    let development = menu.submenu("Development").unwrap();
+    assert!(development.directory_path().is_none());
+    assert_eq!(development.display_name(), "Development");
+}
+
+#[test]
+fn test_invalid_menu_files() {
+    let dir = TestDir::new("invalid");
+    let temp_file = dir.write("invalid_root.menu", "<Layout></Layout>");
+    assert!(matches!(Menu::from_path(&temp_file), Err(MenuError::InvalidFormat(_))));
+
+    dir.write("invalid_root.menu", "<Menu><Name>Broken</Menu>");
+    assert!(matches!(Menu::from_path(&temp_file), Err(MenuError::InvalidFormat(_))));
+
+    assert!(matches!(Menu::from_path("/nonexistent/applications.menu"), Err(MenuError::IoError(_))));
+}
+
+#[test]
+fn test_installed_files_parsed_leniently() {
+    let dir = TestDir::new("lenient");
+    dir.write(
+        "apps/wine-game.desktop",
+        "[Desktop Entry]\nType=Application\nName=Wine Game\nExec=wine game.exe\nCategories=Game;\nX-Wine Key=1\n",
+    );
+    dir.write("directories/games.directory", "[Desktop Entry]\nType=Directory\nName=Games\nX-KDE Key=1\n");
+    let root = dir.write(
+        "applications.menu",
+        &format!(
+            "<Menu>\n  <Name>Applications</Name>\n  <AppDir>{}</AppDir>\n  <DirectoryDir>{}</DirectoryDir>\n  <Menu>\n    <Name>Games</Name>\n    <Directory>games.directory</Directory>\n    <Include><Category>Game</Category></Include>\n  </Menu>\n</Menu>\n",
+            dir.join("apps").display(),
+            dir.join("directories").display()
+        ),
+    );
+
+    // Invalid lines like the key with a space don't drop the files
+    let games = Menu::from_path(&root).unwrap().submenu("Games").cloned().unwrap();
+    assert_eq!(games.entry_ids(), ["wine-game.desktop"]);
+    assert_eq!(games.display_name(), "Games");
+}
+
+#[test]
+fn test_menu_file_lookup_and_default_merge_dirs() {
+    let dir = setup();
+
+    let path = menu_file_path().expect("Menu file should be found");
+    assert_eq!(path, dir.join("etc/xdg/menus/test-applications.menu"));
+
+    let menu = Menu::load().expect("Failed to load menu");
+    assert_eq!(submenu_names(&menu), vec!["Editors"]);
+    assert_eq!(entry_names(menu.submenu("Editors").unwrap()), vec!["Editor"]);
+}
+
+#[test]
+fn test_layout() {
+    let menu = Menu::from_path(fixture_path("layout.menu")).expect("Failed to load menu");
+
+    // Development has a single entry and is inlined as an alias,
+    // Games has more than inline_limit entries and stays a submenu,
+    // Graphics turns off inline_alias and gets a header instead.
+    // Empty is only shown because of show_empty.
+    assert_eq!(
+        layout_items(&menu),
+        vec![
+            "entry:misc.desktop",
+            "---",
+            "menu:Empty",
+            "alias:Development:ide.desktop",
+            "menu:Games",
+            "header:Graphics",
+            "entry:gimp.desktop",
+            "---",
+            "entry:core.desktop",
+        ]
+    );
+
+    let games = menu.submenu("Games").unwrap();
+    assert_eq!(layout_items(games), vec!["entry:tetris.desktop", "entry:chess.desktop"]);
+}
+
+#[test]
+fn test_menu_editor() {
+    let dir = TestDir::new("editor");
+    let edits = dir.join("edits.menu");
+    let root = dir.write(
+        "applications.menu",
+        &format!(
+            "<Menu>\n  <Name>Applications</Name>\n  <AppDir>{}</AppDir>\n  <Menu>\n    <Name>Games</Name>\n    <Include><Category>Game</Category></Include>\n  </Menu>\n  <Menu>\n    <Name>Development</Name>\n    <Include><Category>Development</Category></Include>\n  </Menu>\n  <MergeFile>edits.menu</MergeFile>\n</Menu>\n",
+            fixture_path("apps")
+        ),
+    );
+
+    let mut editor = MenuEditor::from_path(&edits).unwrap();
+    editor.move_entry("chess", "Games", "Development");
+    editor.hide_menu("Graphics");
+    editor.set_layout(
+        "",
+        &[
+            LayoutNode::Menuname("Games".to_string(), Default::default()),
+            LayoutNode::Separator,
+            LayoutNode::Merge(freedesktop_apps::MergeType::All),
+        ],
+    );
+    editor.save().unwrap();
+
+    let menu = Menu::from_path(&root).unwrap();
+    assert_eq!(entry_names(menu.submenu("Games").unwrap()), vec!["Tetris"]);
+    assert_eq!(entry_names(menu.submenu("Development").unwrap()), vec!["Chess", "IDE", "Old IDE"]);
+    assert_eq!(layout_items(&menu), vec!["menu:Games", "---", "menu:Development"]);
+
+    // Saved edits load back unchanged
+    let reloaded = MenuEditor::from_path(&edits).unwrap();
+    assert_eq!(reloaded.to_xml(), editor.to_xml());
+
+    editor.reset().unwrap();
+    assert!(!edits.exists());
+}
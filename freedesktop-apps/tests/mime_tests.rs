@@ -0,0 +1,144 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::{ApplicationEntry, DesktopFileIndex, MimeApps, MimeAppsList};
+
+fn write_app(dir: &TestDir, file: &str, extra: &str) {
+    dir.write_app("share", &format!("{}.desktop", file), file, extra);
+}
+
+/// Every test in this file shares the same fake XDG tree
+fn setup() {
+    xdg_root(|dir| {
+        write_app(dir, "browser", "MimeType=x-scheme-handler/http;x-scheme-handler/https;text/html;\n");
+        write_app(dir, "other-browser", "MimeType=x-scheme-handler/http;x-scheme-handler/https;\n");
+        write_app(dir, "steam", "MimeType=x-scheme-handler/steam;\n");
+        write_app(dir, "mail", "MimeType=x-scheme-handler/mailto;\n");
+        write_app(dir, "desktop-mail", "MimeType=x-scheme-handler/mailto;\n");
+        write_app(dir, "files", "MimeType=inode/directory;\n");
+        write_app(dir, "console", "Categories=System;TerminalEmulator;\n");
+        write_app(dir, "editor", "MimeType=text/plain;text/x-csrc;\n");
+        write_app(dir, "viewer", "MimeType=text/plain;image/png;\n");
+
+        dir.write(
+            "config/mimeapps.list",
+            "[Default Applications]\n\
+             x-scheme-handler/http=missing.desktop;browser.desktop;\n\
+             x-scheme-handler/mailto=mail.desktop\n\
+             text/plain=editor.desktop;\n\
+             \n\
+             [Added Associations]\n\
+             image/png=editor.desktop;\n\
+             \n\
+             [Removed Associations]\n\
+             text/plain=viewer.desktop;\n",
+        );
+        dir.write(
+            "config/test-mimeapps.list",
+            "[Default Applications]\nx-scheme-handler/mailto=desktop-mail.desktop;\n",
+        );
+        dir.write("etc/xdg/mimeapps.list", "[Default Applications]\ntext/plain=viewer.desktop;\n");
+        dir.write("etc/xdg/xdg-terminals.list", "# Preferred terminals\nconsole.desktop\n");
+
+        vec![
+            ("XDG_CONFIG_HOME", "config"),
+            ("XDG_CONFIG_DIRS", "etc/xdg"),
+            ("XDG_DATA_HOME", "data_home"),
+            ("XDG_DATA_DIRS", "share"),
+            ("XDG_CURRENT_DESKTOP", "Test:Other"),
+        ]
+    });
+}
+
+fn entry_id(entry: Option<ApplicationEntry>) -> Option<String> {
+    entry.and_then(|e| e.id())
+}
+
+#[test]
+fn test_mimeapps_list_parsing() {
+    let list = MimeAppsList::parse(
+        "# comment\n[Default Applications]\nText/Plain=a.desktop;b.desktop;\n\n[Added Associations]\ntext/plain=c.desktop\n[Unknown Group]\ntext/plain=d.desktop;\n",
+    );
+
+    assert_eq!(list.default_applications("text/plain"), ["a.desktop", "b.desktop"]);
+    assert_eq!(list.added_associations("TEXT/PLAIN"), ["c.desktop"]);
+    assert!(list.removed_associations("text/plain").is_empty());
+    assert!(list.default_applications("image/png").is_empty());
+}
+
+#[test]
+fn test_scheme_handler_resolution() {
+    setup();
+
+    assert_eq!(entry_id(ApplicationEntry::default_for_scheme("steam")), Some("steam".to_string()));
+    assert_eq!(entry_id(ApplicationEntry::default_for_scheme("unknown")), None);
+}
+
+#[test]
+fn test_default_skips_uninstalled_entries() {
+    setup();
+
+    assert_eq!(entry_id(ApplicationEntry::default_web_browser()), Some("browser".to_string()));
+}
+
+#[test]
+fn test_desktop_specific_list_takes_precedence() {
+    setup();
+
+    assert_eq!(entry_id(ApplicationEntry::default_mail_client()), Some("desktop-mail".to_string()));
+}
+
+#[test]
+fn test_default_file_manager() {
+    setup();
+
+    assert_eq!(entry_id(ApplicationEntry::default_file_manager()), Some("files".to_string()));
+}
+
+#[test]
+fn test_default_terminal() {
+    setup();
+
+    assert_eq!(entry_id(ApplicationEntry::default_terminal()), Some("console".to_string()));
+}
+
+#[test]
+fn test_removed_associations() {
+    setup();
+
+    let mime_apps = MimeApps::load();
+    assert_eq!(entry_id(mime_apps.default_application("text/plain")), Some("editor".to_string()));
+
+    // viewer.desktop is the default in the system list, but the user list removed it
+    let ids: Vec<String> = mime_apps
+        .associated_applications("text/plain")
+        .iter()
+        .filter_map(|e| e.id())
+        .collect();
+    assert_eq!(ids, vec!["editor".to_string()]);
+}
+
+#[test]
+fn test_added_associations_come_first() {
+    setup();
+
+    let ids: Vec<String> = ApplicationEntry::all_for_mime_type("image/png")
+        .iter()
+        .filter_map(|e| e.id())
+        .collect();
+    assert_eq!(ids, vec!["editor".to_string(), "viewer".to_string()]);
+}
+
+#[test]
+fn test_explicit_index() {
+    let dir = TestDir::new("index");
+    dir.write_app("share", "notes.desktop", "Notes", "MimeType=text/plain;\n");
+    dir.write_app("share", "pad.desktop", "Pad", "MimeType=text/plain;\n");
+
+    let list = MimeAppsList::parse("[Default Applications]\ntext/plain=missing.desktop;pad.desktop;\n");
+    let mime_apps = MimeApps::from_lists(vec![list]).with_index(DesktopFileIndex::from_dirs(&[dir.join("share/applications")]));
+
+    assert_eq!(entry_id(mime_apps.default_application("text/plain")), Some("pad".to_string()));
+    assert_eq!(mime_apps.associated_applications("text/plain").len(), 2);
+    assert!(mime_apps.default_application("image/png").is_none());
+}
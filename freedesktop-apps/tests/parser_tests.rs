@@ -211,4 +211,93 @@ fn test_path_method() {
     let entry = ApplicationEntry::try_from_path(&path).expect("Failed to parse");
     
     assert_eq!(entry.path(), Path::new(&path));
+}
+
+#[test]
+fn test_schema_typing() {
+    let entry = ApplicationEntry::from_str(
+        "[Desktop Entry]\nType=Application\nName=2048\nGenericName=True\nComment=Fast; simple\nExec=2048\nCategories=Game\nX-Score=100\nX-Tags=a;b\\;c;\nX-Enabled=true\n",
+        None,
+    )
+    .expect("Failed to parse schema test");
+
+    assert_eq!(entry.name(), Some("2048".to_string()));
+    assert_eq!(entry.generic_name(), Some("True".to_string()));
+    assert_eq!(entry.comment(), Some("Fast; simple".to_string()));
+    assert_eq!(entry.categories(), Some(vec!["Game".to_string()]));
+    assert_eq!(freedesktop_apps::key_type("Comment"), Some(freedesktop_apps::KeyType::LocaleString));
+    assert_eq!(freedesktop_apps::key_type("X-Score"), None);
+
+    // Extension keys are typed by the accessor asking for them
+    assert_eq!(entry.get_string("X-Score"), Some("100".to_string()));
+    assert_eq!(entry.get_numeric("X-Score"), Some(100.0));
+    assert_eq!(entry.get_vec("X-Tags"), Some(vec!["a".to_string(), "b;c".to_string()]));
+    assert_eq!(entry.get_bool("X-Enabled"), Some(true));
+    assert_eq!(entry.get_bool("X-Score"), None);
+}
+
+#[test]
+fn test_parse_from_memory() {
+    let content = "[Desktop Entry]\nType=Application\nName=Memory App\nExec=sh %k\n";
+
+    let entry = ApplicationEntry::from_str(content, None).unwrap();
+    assert_eq!(entry.name(), Some("Memory App".to_string()));
+    assert_eq!(entry.id(), None);
+    assert_eq!(entry.prepare_command(&[], &[]).unwrap(), vec![("sh".to_string(), vec![])]);
+
+    let path = Path::new("/opt/vendor/applications/tools/memory.desktop");
+    let entry = ApplicationEntry::from_bytes(content.as_bytes(), Some(path)).unwrap();
+    assert_eq!(entry.path(), path);
+    assert_eq!(entry.id(), Some("tools-memory".to_string()));
+    assert_eq!(
+        entry.prepare_command(&[], &[]).unwrap(),
+        vec![("sh".to_string(), vec![path.display().to_string()])]
+    );
+
+    let entry = ApplicationEntry::from_reader(std::io::Cursor::new(content), None).unwrap();
+    assert_eq!(entry.exec(), Some("sh %k".to_string()));
+
+    assert!(matches!(
+        ApplicationEntry::from_bytes(b"[Desktop Entry]\nName=\xff\n", None),
+        Err(ParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        ApplicationEntry::from_str("[Desktop Entry]\nType=Application\n", None),
+        Err(ParseError::MissingRequiredKey(_))
+    ));
+}
+
+#[test]
+fn test_lenient_parsing() {
+    use freedesktop_apps::ParseOptions;
+
+    let content: &[u8] = b"\xef\xbb\xbf[Desktop Entry]\r\nType=Application\r\nName=Caf\xe9\r\nExec=wine app.exe\r\nX-Wine Key=1\r\n[Broken\r\njunk\r\n";
+
+    assert!(matches!(ApplicationEntry::from_bytes(content, None), Err(ParseError::InvalidFormat(_))));
+    assert!(matches!(
+        ApplicationEntry::from_str("\u{feff}[Desktop Entry]\nType=Application\nName=x\nExec=x\n", None),
+        Err(ParseError::InvalidFormat(_))
+    ));
+
+    let entry = ApplicationEntry::from_bytes_with_options(content, None, &ParseOptions::lenient()).unwrap();
+    assert_eq!(entry.name(), Some("Café".to_string()));
+    assert_eq!(entry.exec(), Some("wine app.exe".to_string()));
+
+    let warnings: Vec<(usize, usize, &str)> = entry.warnings().iter().map(|w| (w.line, w.column, w.rule)).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (1, 1, "byte-order-mark"),
+            (3, 9, "invalid-utf8"),
+            (5, 1, "invalid-key-name"),
+            (6, 1, "invalid-group-header"),
+            (7, 1, "invalid-line"),
+        ]
+    );
+
+    // Lenient parsing still needs the required keys
+    assert!(matches!(
+        ApplicationEntry::from_bytes_with_options(b"[Desktop Entry]\nName=x\n", None, &ParseOptions::lenient()),
+        Err(ParseError::MissingRequiredKey(_))
+    ));
 }
\ No newline at end of file
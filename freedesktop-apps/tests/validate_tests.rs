@@ -0,0 +1,154 @@
+use freedesktop_apps::{validate, validate_path, Diagnostic, Severity};
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn rules(diagnostics: &[Diagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|d| d.rule).collect()
+}
+
+#[test]
+fn test_valid_files() {
+    for name in ["minimal_app.desktop", "link_entry.desktop", "dbus_activatable.desktop"] {
+        let diagnostics = validate_path(fixture_path(name)).unwrap();
+        assert!(diagnostics.is_empty(), "{}: {:?}", name, diagnostics);
+    }
+}
+
+#[test]
+fn test_positions() {
+    let diagnostics = validate_path(fixture_path("complete_app.desktop")).unwrap();
+    let exec = diagnostics
+        .iter()
+        .find(|d| d.rule == "multiple-file-field-codes")
+        .unwrap();
+
+    // Exec=test-app --mode=%f %F
+    assert_eq!((exec.line, exec.column), (14, 25));
+    assert_eq!(exec.severity, Severity::Error);
+    assert_eq!(
+        exec.to_string(),
+        "14:25: error: Exec may contain only one of %f, %F, %u and %U, found %f and %F [multiple-file-field-codes]"
+    );
+
+    let diagnostics = validate_path(fixture_path("malformed_missing_required.desktop")).unwrap();
+    assert_eq!(rules(&diagnostics), vec!["missing-required-key", "missing-required-key"]);
+    assert!(diagnostics.iter().all(|d| d.line == 1 && d.is_error()));
+}
+
+#[test]
+fn test_keys_and_values() {
+    let content = "[Desktop Entry]\n\
+                   Type=Application\n\
+                   Name=App\n\
+                   Name=Again\n\
+                   Exec=app %x %d\n\
+                   Terminal=yes\n\
+                   NoDisplay=1\n\
+                   Encoding=UTF-8\n\
+                   Colour=red\n\
+                   X-Vendor-Thing=anything\n\
+                   Exec[de]=app\n\
+                   URL=https://example.com\n\
+                   Categories=Utility;NotACategory;X-Mine\n\
+                   Comment=bad \\q escape\n\
+                   Bad Key=1\n";
+
+    let found: Vec<(usize, usize, &str)> = validate(content.as_bytes())
+        .iter()
+        .map(|d| (d.line, d.column, d.rule))
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            (4, 1, "duplicate-key"),
+            (5, 10, "invalid-field-code"),
+            (5, 13, "deprecated-field-code"),
+            (6, 10, "invalid-boolean"),
+            (7, 11, "deprecated-boolean"),
+            (8, 1, "deprecated-key"),
+            (9, 1, "unknown-key"),
+            (11, 1, "invalid-localized-key"),
+            (12, 1, "key-not-applicable"),
+            (13, 20, "invalid-category"),
+            (13, 39, "list-missing-semicolon"),
+            (14, 13, "invalid-escape"),
+            (15, 1, "invalid-key-name"),
+        ]
+    );
+}
+
+#[test]
+fn test_exec_and_duplicate_keys() {
+    let content = "[Desktop Entry]\n\
+                   Type=Link\n\
+                   Type=Application\n\
+                   Name=App\n\
+                   Exec=app \"open\n\
+                   Exec=app --files=%F\n\
+                   TryExec=app > log\n\
+                   Actions=new;\n\
+                   [Desktop Action new]\n\
+                   Name=New\n\
+                   Exec=app --new $HOME\n";
+
+    let found: Vec<(usize, usize, &str)> = validate(content.as_bytes())
+        .iter()
+        .map(|d| (d.line, d.column, d.rule))
+        .collect();
+
+    // The last Type wins, so there is no complaint about a missing URL
+    assert_eq!(
+        found,
+        vec![
+            (3, 1, "duplicate-key"),
+            (5, 6, "unterminated-quote"),
+            (6, 1, "duplicate-key"),
+            (6, 18, "field-code-not-standalone"),
+            (11, 16, "reserved-character"),
+        ]
+    );
+}
+
+#[test]
+fn test_structure() {
+    let content = b"Name=Orphan\n\
+                    [Desktop Entry]\n\
+                    Type=Application\n\
+                    Name=App\n\
+                    Exec=app\n\
+                    Actions=new;missing;\n\
+                    not a key value pair\n\
+                    [Desktop Action new]\n\
+                    Exec=app --new\n\
+                    [Desktop Action extra]\n\
+                    Name=Extra\n\
+                    [Desktop Entry]\n\
+                    [Vendor Stuff]\n\
+                    Comment=caf\xe9\n";
+
+    let found: Vec<(usize, usize, Severity, &str)> = validate(content)
+        .iter()
+        .map(|d| (d.line, d.column, d.severity, d.rule))
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            (1, 1, Severity::Error, "key-before-group"),
+            (6, 13, Severity::Error, "missing-action-group"),
+            (7, 1, Severity::Error, "invalid-line"),
+            (8, 1, Severity::Error, "missing-required-key"),
+            (10, 1, Severity::Warning, "unused-action-group"),
+            (12, 1, Severity::Error, "duplicate-group"),
+            (13, 1, Severity::Error, "unknown-group"),
+            (14, 12, Severity::Error, "invalid-utf8"),
+        ]
+    );
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::{xdg_root, TestDir};
+use freedesktop_apps::ApplicationEntry;
+use freedesktop_core::info::Info;
+use std::fs;
+
+fn temp_entry(file_name: &str, extra: &str) -> ApplicationEntry {
+    let dir = TestDir::new("visibility");
+    let path = dir.write(
+        file_name,
+        &format!("[Desktop Entry]\nType=Application\nName=Test\nExec=test\n{}", extra),
+    );
+    ApplicationEntry::try_from_path(path).unwrap()
+}
+
+#[test]
+fn test_only_show_in() {
+    let entry = temp_entry("visibility_only.desktop", "OnlyShowIn=GNOME;KDE;\n");
+
+    assert!(entry.should_show_in(&["GNOME"]));
+    assert!(entry.should_show_in(&["ubuntu", "KDE"]));
+    assert!(!entry.should_show_in(&["XFCE"]));
+    assert!(!entry.should_show_in(&["gnome"]));
+    assert!(!entry.should_show_in::<&str>(&[]));
+}
+
+#[test]
+fn test_not_show_in() {
+    let entry = temp_entry("visibility_not.desktop", "NotShowIn=KDE\n");
+
+    assert!(entry.should_show_in(&["GNOME"]));
+    assert!(!entry.should_show_in(&["KDE"]));
+    assert!(!entry.should_show_in(&["ubuntu", "KDE"]));
+    assert!(entry.should_show_in::<&str>(&[]));
+}
+
+#[test]
+fn test_first_matching_desktop_decides() {
+    let entry = temp_entry("visibility_order.desktop", "OnlyShowIn=GNOME;\nNotShowIn=Unity;\n");
+
+    assert!(!entry.should_show_in(&["Unity", "GNOME"]));
+    assert!(entry.should_show_in(&["GNOME", "Unity"]));
+}
+
+#[test]
+fn test_should_show_uses_current_desktop() {
+    let entry = temp_entry("visibility_current.desktop", "OnlyShowIn=GNOME;\n");
+    let hidden = temp_entry("visibility_hidden.desktop", "NoDisplay=true\n");
+
+    // should_show() passes XDG_CURRENT_DESKTOP split at ':' to should_show_in()
+    assert!(entry.should_show_in(&["ubuntu", "GNOME"]));
+    assert!(!entry.should_show_in(&["KDE"]));
+    assert_eq!(entry.should_show(), entry.should_show_in(&Info::current_desktops()));
+
+    // NoDisplay hides the entry whatever the desktop
+    assert!(hidden.should_show_in(&["GNOME"]));
+    assert!(!hidden.should_show());
+}
+
+#[test]
+fn test_try_exec_requires_executable_bit() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TestDir::new("try_exec");
+    let program = dir.write("visibility_try_exec_program", "#!/bin/sh\n");
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let entry = temp_entry("visibility_try_exec.desktop", &format!("TryExec={}\n", program.display()));
+    assert!(!entry.is_available());
+
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(entry.is_available());
+
+    fs::remove_file(&program).ok();
+    assert!(!entry.is_available());
+
+    let untested = temp_entry("visibility_no_try_exec.desktop", "");
+    assert!(untested.is_available());
+}
+
+#[test]
+fn test_try_exec_path_lookup_is_cached() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = xdg_root(|_| vec![("PATH", "bin")]);
+    let program = root.write("bin/visibility-cached-program", "#!/bin/sh\n");
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let entry = temp_entry("visibility_cached.desktop", "TryExec=visibility-cached-program\n");
+    assert!(!entry.is_available());
+
+    // The failed lookup is remembered until the cache is cleared
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(!entry.is_available());
+
+    freedesktop_apps::clear_executable_cache();
+    assert!(entry.is_available());
+}
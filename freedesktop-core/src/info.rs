@@ -10,4 +10,33 @@ impl Info {
 
         None
     }
+
+    /// The desktop names from XDG_CURRENT_DESKTOP, in order of
+    /// preference. The variable is a colon separated list,
+    /// e.g. "ubuntu:GNOME".
+    pub fn current_desktops() -> Vec<String> {
+        Self::current_desktop()
+            .map(|desktops| {
+                desktops
+                    .split(':')
+                    .filter(|d| !d.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The locale used for messages, from LC_ALL, LC_MESSAGES or
+    /// LANG in that order, e.g. "sr_YU@Latn". The "C" and "POSIX"
+    /// locales mean no translation and return None.
+    pub fn current_locale() -> Option<String> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .filter(|locale| {
+                let name = locale.split('.').next().unwrap_or_default();
+                name != "C" && name != "POSIX"
+            })
+    }
 }
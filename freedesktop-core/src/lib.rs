@@ -69,3 +69,8 @@ pub fn xdg_data_dirs() -> Vec<PathBuf> {
         .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
     raw.split(':').map(PathBuf::from).collect()
 }
+
+pub fn xdg_config_dirs() -> Vec<PathBuf> {
+    let raw = std::env::var("XDG_CONFIG_DIRS").unwrap_or_else(|_| "/etc/xdg".to_string());
+    raw.split(':').map(PathBuf::from).collect()
+}
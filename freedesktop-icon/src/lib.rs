@@ -151,6 +151,14 @@ impl IconTheme {
         // Pixmaps are a last resort
         Pixmap::get(icon_name)
     }
+
+    /// Get the icon for a MIME type following the shared-mime-info
+    /// lookup chain. See mime_icon_names() for the order.
+    pub fn mime_icon(&self, mime_type: &str) -> Option<PathBuf> {
+        mime_icon_names(mime_type)
+            .iter()
+            .find_map(|icon_name| self.get(icon_name))
+    }
 }
 
 impl IconTheme {
@@ -266,6 +274,72 @@ impl Pixmap {
     }
 }
 
+/// The icon names to try for a MIME type, in order, as defined
+/// by the shared-mime-info spec. Aliases such as application/x-pdf
+/// are first resolved to their canonical type, then the names are:
+/// 1. The override from the `icons` file of the MIME database
+/// 2. The MIME type with '/' replaced by '-' (text/plain -> text-plain)
+/// 3. The generic icon from the `generic-icons` file
+/// 4. The media type generic icon (text/plain -> text-x-generic)
+pub fn mime_icon_names(mime_type: &str) -> Vec<String> {
+    let mut data_dirs = vec![freedesktop_core::xdg_data_home()];
+    data_dirs.extend(freedesktop_core::xdg_data_dirs());
+
+    mime_icon_names_from_dirs(mime_type, &data_dirs)
+}
+
+/// Same as mime_icon_names() but reads the MIME database from the
+/// mime/ directory of the given data dirs, most important first
+pub fn mime_icon_names_from_dirs(mime_type: &str, data_dirs: &[PathBuf]) -> Vec<String> {
+    let mut mime_type = mime_type.to_lowercase();
+    if let Some(canonical) = mime_database_lookup(data_dirs, "aliases", ' ', &mime_type) {
+        mime_type = canonical.to_lowercase();
+    }
+
+    let mut names: Vec<String> = Vec::new();
+
+    if let Some(icon) = mime_database_lookup(data_dirs, "icons", ':', &mime_type) {
+        names.push(icon);
+    }
+
+    names.push(mime_type.replace('/', "-"));
+
+    if let Some(icon) = mime_database_lookup(data_dirs, "generic-icons", ':', &mime_type) {
+        names.push(icon);
+    }
+
+    if let Some((media, _)) = mime_type.split_once('/') {
+        names.push(format!("{}-x-generic", media));
+    }
+
+    names.dedup();
+    names
+}
+
+// The aliases, icons and generic-icons files live in the mime/
+// directory of every data dir. Each line is "mime/type:icon-name",
+// or "alias/type canonical/type" for aliases. Like the rest of the
+// MIME database, the first data dir with a match wins.
+fn mime_database_lookup(data_dirs: &[PathBuf], file_name: &str, separator: char, mime_type: &str) -> Option<String> {
+    for data_dir in data_dirs {
+        let Ok(content) = std::fs::read_to_string(data_dir.join("mime").join(file_name)) else {
+            continue;
+        };
+
+        for line in content.lines() {
+            let Some((mime, value)) = line.split_once(separator) else {
+                continue;
+            };
+
+            if mime.trim().eq_ignore_ascii_case(mime_type) && !value.trim().is_empty() {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+
+    None
+}
+
 /// Convenience function that will:
 /// Get the current icon theme from IconTheme::current()
 /// Call theme.get() which will get the icon for
@@ -276,3 +350,9 @@ impl Pixmap {
 pub fn get_icon(name: &str) -> Option<PathBuf> {
     CURRENT_ICON_THEME.get(name)
 }
+
+/// Same as get_icon() but looks up the icon for a MIME type
+/// using the current icon theme
+pub fn mime_icon(mime_type: &str) -> Option<PathBuf> {
+    CURRENT_ICON_THEME.mime_icon(mime_type)
+}
@@ -0,0 +1,58 @@
+use freedesktop_icon::mime_icon_names_from_dirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A data dir with a mime/ directory holding the given files
+fn data_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("mime_tests-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(dir.join("mime")).unwrap();
+
+    for (file, content) in files {
+        fs::write(dir.join("mime").join(file), content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_mime_icon_name_chain() {
+    let dirs = [data_dir(
+        "chain",
+        &[
+            ("icons", "application/x-custom:custom-icon\n"),
+            ("generic-icons", "text/x-csrc:text-x-script\napplication/pdf:x-office-document\n"),
+        ],
+    )];
+
+    assert_eq!(
+        mime_icon_names_from_dirs("text/x-csrc", &dirs),
+        vec!["text-x-csrc", "text-x-script", "text-x-generic"]
+    );
+    assert_eq!(
+        mime_icon_names_from_dirs("application/x-custom", &dirs),
+        vec!["custom-icon", "application-x-custom", "application-x-generic"]
+    );
+
+    // Without a database entry the media type generic icon is last
+    assert_eq!(mime_icon_names_from_dirs("Video/MP4", &dirs), vec!["video-mp4", "video-x-generic"]);
+    assert_eq!(mime_icon_names_from_dirs("video/mp4", &[]), vec!["video-mp4", "video-x-generic"]);
+}
+
+#[test]
+fn test_mime_icon_aliases() {
+    let user = data_dir("user", &[("generic-icons", "application/pdf:user-document\n")]);
+    let system = data_dir(
+        "system",
+        &[
+            ("aliases", "application/x-pdf application/pdf\ntext/x-c text/x-csrc\n"),
+            ("generic-icons", "application/pdf:x-office-document\n"),
+        ],
+    );
+    let dirs = [user, system];
+
+    assert_eq!(
+        mime_icon_names_from_dirs("application/x-pdf", &dirs),
+        vec!["application-pdf", "user-document", "application-x-generic"]
+    );
+    assert_eq!(mime_icon_names_from_dirs("text/x-c", &dirs), vec!["text-x-csrc", "text-x-generic"]);
+}
@@ -40,12 +40,10 @@
 //!     }
 //! }
 //!
-//! // Parse a desktop file (would normally execute an app)
-//! # let desktop_content = "[Desktop Entry]\nType=Application\nName=Test App\nExec=test-app\n";
-//! # std::fs::write("/tmp/test.desktop", desktop_content).unwrap();
-//! let app = ApplicationEntry::try_from_path("/tmp/test.desktop").unwrap();
+//! // Parse a desktop entry (would normally execute an app)
+//! let desktop_content = "[Desktop Entry]\nType=Application\nName=Test App\nExec=test-app\n";
+//! let app = ApplicationEntry::from_str(desktop_content, None).unwrap();
 //! // app.execute().unwrap(); // Would launch the application
-//! # std::fs::remove_file("/tmp/test.desktop").ok();
 //! # }
 //! ```
 //!